@@ -4,7 +4,12 @@ use std::io::{self, BufRead};
 
 #[cfg(feature = "cli")]
 fn main() {
-    use braces::{brace_paths, pretty_braces, BraceConfig};
+    use braces::{
+        brace_paths, brace_paths_budgeted, brace_paths_regex, brace_paths_with_hint,
+        combine_braces, combine_paths, diff_paths, expand_braces_with, powershell_array,
+        pretty_braces_with, summarise_paths, BaseDisplay, BraceConfig, CaseFolding, Direction,
+        EscapePolicy, LeadingDot, OutsideBase, PathStyle, SetOperation, VerifyMode,
+    };
 
     #[cfg(feature = "highlight")]
     use anstream::println;
@@ -23,13 +28,29 @@ fn main() {
     let mut config = BraceConfig::default();
     let mut paths = Vec::new();
     let mut pretty_print = false;
+    let mut powershell = false;
+    let mut regex = false;
     let mut read_null = false;
+    let mut budget = None;
+    let mut zoom = None;
+    let mut hint = None;
+    let mut set_op = None;
+    let mut diff_from = None;
+    // Options choosing what is printed, which cannot be combined
+    let mut modes: Vec<&str> = vec![];
     let mut i = 0;
 
     while i < args.len() {
         let arg = &args[i];
+        if is_mode(arg) && !modes.contains(&arg.as_str()) {
+            modes.push(arg);
+        }
         match arg.as_str() {
             "--pretty" => pretty_print = true,
+            "--powershell" => powershell = true,
+            "--regex" => regex = true,
+            "--windows" => config.path_style = PathStyle::Windows,
+            "--url" => config.path_style = PathStyle::Url,
             "--sort" => config.sort_items = true,
             "--stem-split" => config.allow_stem_split = true,
             "--no-segment-split" => config.allow_segment_split = false,
@@ -38,6 +59,39 @@ fn main() {
             "--reprocess" => config.reprocess_braces = true,
             "--allow-mixed-sep" => config.allow_mixed_separators = true,
             "--preserve-order" => config.preserve_order_within_braces = true,
+            "--stable" => config.stable_output = true,
+            "--right-to-left" => config.direction = Direction::RightToLeft,
+            "--keep-outside" => config.outside_base = OutsideBase::KeepAbsolute,
+            "--show-base" => config.base_display = BaseDisplay::Prefix,
+            "--base" => {
+                i += 1;
+                if i < args.len() {
+                    config.base = Some(args[i].clone());
+                }
+            }
+            "--base-var" => {
+                i += 1;
+                if i < args.len() {
+                    config.base_display = BaseDisplay::Variable(args[i].clone());
+                }
+            }
+            "--normalise" => {
+                config.normalisation.collapse_separators = true;
+                config.normalisation.remove_dot_segments = true;
+                config.normalisation.resolve_parent_segments = true;
+            }
+            "--leading-dot" => {
+                i += 1;
+                config.normalisation.leading_dot = match args.get(i).map(String::as_str) {
+                    Some("keep") => LeadingDot::Keep,
+                    Some("strip") => LeadingDot::Strip,
+                    Some("enforce") => LeadingDot::Enforce,
+                    other => {
+                        eprintln!("Error: Unknown leading dot mode: {}", other.unwrap_or(""));
+                        std::process::exit(1);
+                    }
+                };
+            }
             #[cfg(feature = "highlight")]
             "--highlight" => config.highlight = true,
             "--separator" => {
@@ -46,6 +100,12 @@ fn main() {
                     config.path_separator = args[i].clone();
                 }
             }
+            "--separators" => {
+                i += 1;
+                if i < args.len() {
+                    config.separators = args[i].split_whitespace().map(str::to_string).collect();
+                }
+            }
             "--max-depth" => {
                 i += 1;
                 if i < args.len() {
@@ -62,6 +122,84 @@ fn main() {
                     }
                 }
             }
+            "--budget" => {
+                i += 1;
+                if i < args.len() {
+                    if let Ok(n) = args[i].parse() {
+                        budget = Some(n);
+                    }
+                }
+            }
+            "--escape" => {
+                i += 1;
+                config.escape = match args.get(i).map(String::as_str) {
+                    Some("backslash") => EscapePolicy::Backslash,
+                    Some("quote") => EscapePolicy::Quote,
+                    Some("shell") => EscapePolicy::Shell,
+                    Some("doubled") => EscapePolicy::QuoteDoubled,
+                    Some("reject") => EscapePolicy::Reject,
+                    other => {
+                        eprintln!("Error: Unknown escape policy: {}", other.unwrap_or(""));
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--verify" => {
+                i += 1;
+                config.verify = match args.get(i).map(String::as_str) {
+                    Some("error") => VerifyMode::Error,
+                    Some("fallback") => VerifyMode::Fallback,
+                    Some("off") => VerifyMode::Off,
+                    other => {
+                        eprintln!("Error: Unknown verify mode: {}", other.unwrap_or(""));
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--case-fold" => {
+                i += 1;
+                config.case_folding = match args.get(i).map(String::as_str) {
+                    Some("first") => CaseFolding::FirstSeen,
+                    Some("common") => CaseFolding::MostCommon,
+                    Some("lower") => CaseFolding::Lowercase,
+                    Some("off") => CaseFolding::Off,
+                    other => {
+                        eprintln!("Error: Unknown case folding: {}", other.unwrap_or(""));
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--hint" => {
+                i += 1;
+                if i < args.len() {
+                    hint = Some(args[i].clone());
+                }
+            }
+            "--zoom" => {
+                i += 1;
+                if i < args.len() {
+                    if let Ok(depth) = args[i].parse() {
+                        zoom = Some(depth);
+                    }
+                }
+            }
+            "--union" | "--intersect" | "--difference" => {
+                let op = match arg.as_str() {
+                    "--union" => SetOperation::Union,
+                    "--intersect" => SetOperation::Intersection,
+                    _ => SetOperation::Difference,
+                };
+                i += 1;
+                if i < args.len() {
+                    set_op = Some((op, args[i].clone()));
+                }
+            }
+            "--diff" => {
+                i += 1;
+                if i < args.len() {
+                    diff_from = Some(args[i].clone());
+                }
+            }
             "-0" | "--null" => read_null = true,
             _ if arg.starts_with("--") => {
                 eprintln!("Error: Unknown option: {}", arg);
@@ -75,6 +213,11 @@ fn main() {
         i += 1;
     }
 
+    if let [first, second, ..] = modes.as_slice() {
+        eprintln!("Error: {} cannot be combined with {}", first, second);
+        std::process::exit(1);
+    }
+
     // Read from stdin if no paths provided
     if paths.is_empty() {
         let stdin = io::stdin();
@@ -103,10 +246,48 @@ fn main() {
         std::process::exit(1);
     }
 
-    match brace_paths(&paths, &config) {
+    // Input and output are read with the escaping in effect for the path style
+    let escape = config.resolved().escape;
+
+    let result = if let Some(before) = diff_from {
+        let inputs: Vec<String> = paths
+            .iter()
+            .flat_map(|p| expand_braces_with(p, escape))
+            .collect();
+        let before = expand_braces_with(&before, escape);
+        match diff_paths(&before, &inputs, &config) {
+            // Nothing changed, so there is nothing to print
+            Ok(diff) if diff.is_empty() => return,
+            diff => diff.map(|d| d.annotated.unwrap_or_default()),
+        }
+    } else if regex {
+        brace_paths_regex(&paths, &config)
+    } else {
+        match (set_op, zoom, budget, hint) {
+            (Some((op, expr)), _, _, _) => {
+                // Inputs and the operand are both brace expressions, read as by `combine_braces`
+                let combined = match paths.as_slice() {
+                    [input] => combine_braces(input, &expr, op, &config),
+                    _ => expand_checked(&paths, escape).and_then(|inputs| {
+                        let operand = expand_checked(&[expr], escape)?;
+                        combine_paths(&inputs, &operand, op, &config)
+                    }),
+                };
+                combined.map(Option::unwrap_or_default)
+            }
+            (None, Some(depth), _, _) => summarise_paths(&paths, &config, depth),
+            (None, None, Some(n), _) => brace_paths_budgeted(&paths, &config, n).map(|b| b.text),
+            (None, None, None, Some(previous)) => brace_paths_with_hint(&paths, &config, &previous),
+            (None, None, None, None) => brace_paths(&paths, &config),
+        }
+    };
+
+    match result {
         Ok(result) => {
-            let output = if pretty_print {
-                pretty_braces(&result)
+            let output = if powershell {
+                powershell_array(&result, &config)
+            } else if pretty_print {
+                pretty_braces_with(&result, escape)
             } else {
                 result
             };
@@ -120,6 +301,38 @@ fn main() {
     }
 }
 
+/// Whether an option chooses what is printed, as only one such option may be given
+#[cfg(feature = "cli")]
+fn is_mode(arg: &str) -> bool {
+    matches!(
+        arg,
+        "--regex"
+            | "--budget"
+            | "--zoom"
+            | "--hint"
+            | "--union"
+            | "--intersect"
+            | "--difference"
+            | "--diff"
+    )
+}
+
+/// Expand brace expressions, failing on any syntax error
+#[cfg(feature = "cli")]
+fn expand_checked(exprs: &[String], escape: braces::EscapePolicy) -> braces::Result<Vec<String>> {
+    let mut expanded = vec![];
+    for expr in exprs {
+        let parsed = braces::parse_braces(expr, escape);
+        if !parsed.errors.is_empty() {
+            return Err(braces::BraceError::InvalidBraceSyntax {
+                errors: parsed.errors,
+            });
+        }
+        expanded.extend(parsed.expr.expand());
+    }
+    Ok(expanded)
+}
+
 #[cfg(feature = "cli")]
 fn print_help() {
     println!("braces - Convert path lists into braces expansion syntax");
@@ -132,6 +345,10 @@ fn print_help() {
     println!("    --pretty              Pretty-print the output with indentation");
     #[cfg(feature = "highlight")]
     println!("    --highlight           Highlight brace groups with colours");
+    println!("    --windows             Windows paths: \\ separator, drive/UNC roots, any case");
+    println!("    --url                 URLs: scheme and host as root, path and query as levels");
+    println!("    --powershell          Print a PowerShell array of the paths");
+    println!("    --regex               Print an anchored regex matching exactly the paths");
     println!("    --sort                Sort items within braces");
     println!("    --stem-split          Enable stem-level character splitting");
     println!("    --no-segment-split    Disable segment splitting (no empty components)");
@@ -140,15 +357,37 @@ fn print_help() {
     println!("    --reprocess           Expand and reprocess existing braces");
     println!("    --allow-mixed-sep     Normalise mixed separators");
     println!("    --preserve-order      Sort within braces even when --sort not used");
+    println!("    --right-to-left       Group segments from the right (e.g. host names)");
+    println!("    --normalise           Collapse //, remove . and resolve .. segments lexically");
+    println!("    --leading-dot MODE    Leading ./ on relative paths: keep, strip or enforce");
+    println!(
+        "    --escape POLICY       Escape {{, }} and , in paths: backslash, quote, shell, doubled or reject"
+    );
+    println!("    --case-fold SPELLING  Group case-insensitively: first, common, lower or off");
+    println!("    --verify MODE         Check output expands to the input: error, fallback or off");
+    println!("    --stable              Keep factoring local so small input changes stay local");
+    println!("    --hint EXPR           Keep the item order of a previous output");
     println!("    --separator SEP       Set path separator (default: /)");
+    println!("    --separators LIST     Space-separated separators to recognise (default: '/ \\')");
     println!("    --max-depth N         Maximum brace nesting depth (default: 5)");
     println!("    --max-brace-size N    Maximum items per brace");
+    println!("    --budget N            Summarise subtrees to fit in N characters (lossy)");
+    println!("    --zoom N              Overview with path counts for groups below depth N");
+    println!("    --union EXPR          Combine the input with the paths of EXPR");
+    println!("    --intersect EXPR      Keep input paths that are also in EXPR");
+    println!("    --difference EXPR     Keep input paths that are not in EXPR");
+    println!("    --diff BEFORE         Show paths added (+) and removed (-) since BEFORE");
     println!("    -0, --null            Read NUL-separated input (like xargs -0)");
     println!("    -h, --help            Print this help message");
     println!();
+    println!("    Only one of --regex, --budget, --zoom, --hint, --union, --intersect,");
+    println!("    --difference and --diff may be given.");
+    println!();
     println!("EXAMPLES:");
     println!("    braces foo/bar.rs foo/baz.rs");
+    println!("    braces --separator . --right-to-left api.example.com web.example.com");
     println!("    echo -e \"foo/bar.rs\\nfoo/baz.rs\" | braces --sort");
+    println!("    braces --intersect 'src/{{a,b}}.rs' 'src/{{b,c}}.rs'");
     #[cfg(feature = "highlight")]
     println!("    braces --highlight --pretty foo/{{bar,baz}}.rs");
 }
@@ -1,3 +1,4 @@
+use crate::processor::ParseError;
 use std::fmt;
 
 pub type Result<T> = std::result::Result<T, BraceError>;
@@ -16,6 +17,17 @@ pub enum BraceError {
     DepthLimitExceeded {
         limit: usize,
     },
+    VerificationFailed {
+        missing: Vec<String>,
+        unexpected: Vec<String>,
+    },
+    OutsideBase {
+        path: String,
+        base: String,
+    },
+    InvalidBraceSyntax {
+        errors: Vec<ParseError>,
+    },
 }
 
 impl fmt::Display for BraceError {
@@ -35,6 +47,31 @@ impl fmt::Display for BraceError {
             BraceError::DepthLimitExceeded { limit } => {
                 write!(f, "Brace depth limit of {} exceeded", limit)
             }
+            BraceError::VerificationFailed {
+                missing,
+                unexpected,
+            } => {
+                if missing.is_empty() && unexpected.is_empty() {
+                    write!(f, "Output does not expand to the input paths in order")
+                } else {
+                    write!(
+                        f,
+                        "Output does not expand to the input paths: missing {:?}, unexpected {:?}",
+                        missing, unexpected
+                    )
+                }
+            }
+            BraceError::OutsideBase { path, base } => {
+                write!(
+                    f,
+                    "Path '{}' is outside the base directory '{}'",
+                    path, base
+                )
+            }
+            BraceError::InvalidBraceSyntax { errors } => {
+                let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                write!(f, "Invalid brace syntax: {}", errors.join("; "))
+            }
         }
     }
 }
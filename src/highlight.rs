@@ -2,38 +2,108 @@
 
 mod ansi;
 
+use crate::processor::parse::parse_highlighted;
+use crate::{EscapePolicy, Renderer, SpannedExpr, SpannedKind};
 use ansi::{Highlight, BLUE, CYAN, GREEN, RED, YELLOW};
 
 const BRACE_COLORS: &[ansi::Color] = &[CYAN, YELLOW, RED, GREEN, BLUE];
 
 /// Highlight braces in the output with cycling colors per nesting level
 pub fn highlight_braces(text: &str) -> String {
+    highlight_braces_with(text, EscapePolicy::Reject)
+}
+
+/// Highlight braces in an expression written under an [`EscapePolicy`]
+///
+/// Only the braces and commas of balanced groups are coloured, so escaped
+/// or quoted metacharacters are left as they are.
+pub fn highlight_braces_with(text: &str, escape: EscapePolicy) -> String {
     let mut result = String::new();
-    let mut depth = 0;
-
-    for ch in text.chars() {
-        match ch {
-            '{' => {
-                let color = BRACE_COLORS[depth % BRACE_COLORS.len()];
-                result.push_str(&ch.color(color).to_string());
-                depth += 1;
+    highlight_sequence(text, &parse_highlighted(text, escape).expr, 0, &mut result);
+    result
+}
+
+/// Write the parts of a sequence, colouring each group by its nesting depth
+fn highlight_sequence(source: &str, expr: &SpannedExpr, depth: usize, result: &mut String) {
+    let SpannedKind::Concat(parts) = &expr.kind else {
+        return;
+    };
+    for part in parts {
+        let color = BRACE_COLORS[depth % BRACE_COLORS.len()];
+        let options = match &part.kind {
+            SpannedKind::Alt(options) => options,
+            SpannedKind::Range(_) => {
+                let inner = &source[part.span.start + 1..part.span.end - 1];
+                result.push_str(&format!(
+                    "{}{}{}",
+                    '{'.color(color),
+                    inner,
+                    '}'.color(color)
+                ));
+                continue;
             }
-            '}' => {
-                depth = depth.saturating_sub(1);
-                let color = BRACE_COLORS[depth % BRACE_COLORS.len()];
-                result.push_str(&ch.color(color).to_string());
+            _ => {
+                result.push_str(&source[part.span.clone()]);
+                continue;
             }
-            ',' => {
-                if depth > 0 {
-                    let color = BRACE_COLORS[(depth - 1) % BRACE_COLORS.len()];
-                    result.push_str(&ch.color(color).to_string());
-                } else {
-                    result.push(ch);
-                }
+        };
+        result.push_str(&'{'.color(color).to_string());
+        for (i, option) in options.iter().enumerate() {
+            if i > 0 {
+                result.push_str(&','.color(color).to_string());
             }
-            _ => result.push(ch),
+            highlight_sequence(source, option, depth + 1, result);
         }
+        result.push_str(&'}'.color(color).to_string());
     }
+}
 
-    result
+/// Braces output with the braces and commas of each group coloured by nesting depth
+///
+/// Writes the same text as [`highlight_braces_with`] gives for the plain
+/// output, straight from the expression tree.
+///
+/// # Example
+/// ```
+/// use braces::highlight::{highlight_braces, AnsiRenderer};
+/// use braces::{brace_paths_expr, BraceConfig, EscapePolicy};
+///
+/// let expr = brace_paths_expr(&["a/b", "a/c"], &BraceConfig::default()).unwrap();
+/// let highlighted = expr.render(&AnsiRenderer, EscapePolicy::Reject);
+/// assert_eq!(highlighted, highlight_braces("a/{b,c}"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AnsiRenderer;
+
+impl AnsiRenderer {
+    /// Write a metacharacter in the colour of groups `depth` deep
+    fn coloured(out: &mut String, c: char, depth: usize) {
+        out.push_str(&c.color(BRACE_COLORS[depth % BRACE_COLORS.len()]));
+    }
+}
+
+impl Renderer for AnsiRenderer {
+    fn literal(&self, out: &mut String, _text: &str, written: &str) {
+        out.push_str(written);
+    }
+
+    fn group_open(&self, out: &mut String, depth: usize) {
+        Self::coloured(out, '{', depth);
+    }
+
+    fn item_separator(&self, out: &mut String, depth: usize) {
+        Self::coloured(out, ',', depth);
+    }
+
+    fn group_close(&self, out: &mut String, depth: usize) {
+        Self::coloured(out, '}', depth);
+    }
+
+    fn empty_alternative(&self, _out: &mut String, _depth: usize) {}
+}
+
+/// Colour a changed path segment: green if added, red if removed
+#[cfg(feature = "highlight")]
+pub(crate) fn highlight_change(text: &str, added: bool) -> String {
+    text.color(if added { GREEN } else { RED })
 }
@@ -17,6 +17,18 @@ mod processor;
 pub mod highlight;
 
 pub use error::{BraceError, Result};
-pub use processor::expansion::expand_braces;
-pub use processor::ppb::pretty_braces;
-pub use processor::{brace_paths, BraceConfig};
+pub use processor::expansion::{expand_braces, expand_braces_with};
+pub use processor::ppb::{pretty_braces, pretty_braces_with};
+#[cfg(feature = "graphemes")]
+pub use processor::GraphemeSegmenter;
+pub use processor::{
+    brace_paths, brace_paths_budgeted, brace_paths_expr, brace_paths_mapped, brace_paths_regex,
+    brace_paths_with, brace_paths_with_hint, brace_sequences, canonicalize, combine_braces,
+    combine_paths, diff_paths, equivalent, parse_braces, powershell_array, summarise_paths,
+    summarise_sized_paths, BaseDisplay, BraceBuilder, BraceConfig, BraceDiff, BraceExpr,
+    BraceMatch, BraceMatcher, BraceRange, BraceRenderer, BudgetedBraces, CaseFolding, Direction,
+    EscapePolicy, GroupChoice, LeadingDot, MappedBraces, MultiSeparatorSegmenter, Normalisation,
+    OutsideBase, ParseError, ParseErrorKind, ParsedBraces, PathStyle, Renderer, Segmenter,
+    SeparatorSegmenter, SetOperation, SourceSpan, SpanKind, SpannedExpr, SpannedKind,
+    TokenSegmenter, VerifyMode,
+};
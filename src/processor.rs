@@ -1,14 +1,67 @@
 use crate::error::{BraceError, Result};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+mod algebra;
+mod ast;
+mod base;
+mod budget;
+mod builder;
+mod canonical;
+mod case;
+mod diff;
+mod direction;
+mod escape;
 pub(crate) mod expansion;
+mod matcher;
 mod normalise;
+pub(crate) mod parse;
 pub mod ppb;
+mod range;
+mod regex;
+mod render;
+mod segment;
+mod sequence;
+mod source_map;
+mod style;
+mod summary;
 mod trie;
+mod url;
+mod verify;
+mod windows;
 
-use expansion::{compute_reprs, expand_braces};
-use normalise::{find_common_suffix, normalise_separators, validate_separators};
-use trie::build_trie;
+pub use algebra::{combine_braces, combine_paths, SetOperation};
+pub use ast::BraceExpr;
+use base::{base_prefix, check_base, displayed_paths, split_base};
+pub use base::{BaseDisplay, OutsideBase};
+pub use budget::{brace_paths_budgeted, BudgetedBraces};
+pub use builder::BraceBuilder;
+pub use canonical::{canonicalize, equivalent};
+pub use case::CaseFolding;
+pub use diff::{diff_paths, BraceDiff};
+pub use direction::Direction;
+pub use escape::EscapePolicy;
+use escape::{escape_text, REJECTED};
+use expansion::{compute_reprs, expand_braces_with};
+pub use matcher::{BraceMatch, BraceMatcher, GroupChoice};
+use normalise::{find_common_suffix, normalise_path, normalise_separators, validate_separators};
+pub use normalise::{LeadingDot, Normalisation};
+pub use parse::{parse_braces, ParseError, ParseErrorKind, ParsedBraces, SpannedExpr, SpannedKind};
+pub use range::BraceRange;
+pub use regex::brace_paths_regex;
+pub use render::{BraceRenderer, Renderer};
+#[cfg(feature = "graphemes")]
+pub use segment::GraphemeSegmenter;
+use segment::PathSegmenter;
+pub use segment::{MultiSeparatorSegmenter, Segmenter, SeparatorSegmenter, TokenSegmenter};
+pub use sequence::brace_sequences;
+pub use source_map::{brace_paths_mapped, MappedBraces, SourceSpan, SpanKind};
+use style::resolve_style;
+pub use style::PathStyle;
+pub use summary::{summarise_paths, summarise_sized_paths};
+use trie::build_trie_with;
+use verify::verify_output;
+pub use verify::VerifyMode;
+pub use windows::powershell_array;
 
 /// Configuration for brace expansion
 ///
@@ -23,6 +76,30 @@ pub struct BraceConfig {
     /// paths with different separators will cause an error.
     pub path_separator: String,
 
+    /// Conventions of the input paths (default: [`PathStyle::Posix`]).
+    ///
+    /// [`PathStyle::Windows`] uses `\` as the separator, keeps drive and UNC
+    /// prefixes together as a root segment, compares case-insensitively and
+    /// quotes rather than backslash-escapes metacharacters.
+    ///
+    /// # Example
+    /// With `PathStyle::Windows`:
+    /// - `["\\\\srv\\share\\a.txt", "\\\\srv\\share\\B.txt"]` → `"\\\\srv\\share\\{a,B}.txt"`
+    pub path_style: PathStyle,
+
+    /// Separators recognised in input paths (default: `["/", "\\"]`).
+    ///
+    /// Any of these other than `path_separator` is reported as a mixed
+    /// separator, or replaced by `path_separator` when `allow_mixed_separators`
+    /// is set. Separators may be several characters long, like `::` or `->`,
+    /// and the longest one matching at each position is taken. `path_separator`
+    /// is always recognised, whether listed or not.
+    ///
+    /// # Example
+    /// With `separators = ["::", "."]`, `path_separator = "::"` and `allow_mixed_separators`:
+    /// - `["std::io::Read", "std.io.Write"]` → `"std::io::{Read,Write}"`
+    pub separators: Vec<String>,
+
     /// Maximum nesting depth of braces (default: `5`).
     ///
     /// Limits how deeply braces can be nested to prevent performance issues.
@@ -119,6 +196,54 @@ pub struct BraceConfig {
     /// are preserved in the output.
     pub deduplicate_inputs: bool,
 
+    /// Which end of the paths segments are grouped from (default: [`Direction::LeftToRight`]).
+    ///
+    /// Right to left suits names that share their suffix, such as host names
+    /// split on `.`. Sorting compares segments from the same end, and the shared
+    /// prefix is factored out in place of the shared suffix.
+    ///
+    /// # Example
+    /// With `path_separator = "."` and `Direction::RightToLeft`:
+    /// - `["api.prod.example.com", "web.prod.example.com"]` → `"{api,web}.prod.example.com"`
+    pub direction: Direction,
+
+    /// Lexical path normalisation steps (default: all disabled).
+    ///
+    /// Applied after separator normalisation and before deduplication, so that
+    /// `./src/a.rs`, `src//a.rs` and `src/x/../a.rs` can all become `src/a.rs`.
+    /// See [`Normalisation`] for the individual steps.
+    ///
+    /// # Example
+    /// With `collapse_separators`, `remove_dot_segments` and `leading_dot: LeadingDot::Strip`:
+    /// - `["./src/a.rs", "src//b.rs"]` → `"src/{a,b}.rs"`
+    pub normalisation: Normalisation,
+
+    /// Base directory to make paths relative to (default: `None`).
+    ///
+    /// Paths under the base are compressed relative to it, which removes a long
+    /// shared root such as `/home/ci/work/repo` from build log paths. Matching is
+    /// on whole segments, after normalisation. How the base is shown is set by
+    /// `base_display`, and paths outside it are handled by `outside_base`.
+    ///
+    /// # Example
+    /// With `base = Some("/home/ci/repo".into())`:
+    /// - `["/home/ci/repo/src/a.rs", "/home/ci/repo/src/b.rs"]` → `"src/{a,b}.rs"`
+    pub base: Option<String>,
+
+    /// What to do with paths outside `base` (default: [`OutsideBase::Reject`]).
+    ///
+    /// # Example
+    /// With `base = Some("/repo".into())` and `OutsideBase::KeepAbsolute`:
+    /// - `["/repo/a.rs", "/repo/b.rs", "/usr/include/c.h"]` → `"{{a,b}.rs,/usr/include/c.h}"`
+    pub outside_base: OutsideBase,
+
+    /// How `base` is shown in the output (default: [`BaseDisplay::Hide`]).
+    ///
+    /// # Example
+    /// With `BaseDisplay::Variable("$ROOT".into())`:
+    /// - `["/repo/a.rs", "/repo/b.rs"]` → `"$ROOT/{a,b}.rs"`
+    pub base_display: BaseDisplay,
+
     /// Expand existing braces in input before reprocessing (default: `false`).
     ///
     /// When `false`, input containing brace syntax will cause an error (prevents
@@ -130,6 +255,57 @@ pub struct BraceConfig {
     /// - Input: `"a/{b,c}.rs"` → Expanded to `["a/b.rs", "a/c.rs"]` → Reprocessed
     pub reprocess_braces: bool,
 
+    /// How literal `{`, `}` and `,` in paths are handled (default: [`EscapePolicy::Reject`]).
+    ///
+    /// By default such paths are rejected, as they would be ambiguous in the
+    /// output. Otherwise, they are escaped with backslashes or quotes, and the
+    /// output round-trips through [`expand_braces_with`] with the same policy.
+    ///
+    /// # Examples
+    /// With [`EscapePolicy::Backslash`]:
+    /// - `["a,b.txt", "c.txt"]` → `"{a\\,b,c}.txt"`
+    ///
+    /// With [`EscapePolicy::Quote`]:
+    /// - `["a,b.txt", "c.txt"]` → `"{'a,b',c}.txt"`
+    pub escape: EscapePolicy,
+
+    /// Group path segments case-insensitively (default: [`CaseFolding::Off`]).
+    ///
+    /// For case-insensitive filesystems, where `Src/Lib.rs` and `src/lib.rs`
+    /// are the same file. Deduplication uses the same folding, and the variant
+    /// chooses which spelling of each segment is printed.
+    ///
+    /// # Example
+    /// With [`CaseFolding::FirstSeen`]:
+    /// - `["Src/Lib.rs", "src/lib.rs", "src/main.rs"]` → `"Src/{Lib,main}.rs"`
+    pub case_folding: CaseFolding,
+
+    /// Check that the output expands back to the input (default: [`VerifyMode::Off`]).
+    ///
+    /// After compressing, the output is expanded and compared to the
+    /// deduplicated input paths, as a set or, when order is preserved, as a
+    /// sequence. On a mismatch, either an error listing the differing paths is
+    /// returned or the output falls back to a flat `{p1,p2,...}` listing.
+    /// Lossy output from [`brace_paths_budgeted`] is never verified.
+    pub verify: VerifyMode,
+
+    /// Keep factoring decisions local to each group (default: `false`).
+    ///
+    /// By default, a common suffix shared by all paths is factored out of the
+    /// whole expression, so adding one path with a different suffix reshapes
+    /// the entire output. When enabled, suffixes and stem splits are only
+    /// factored within groups of plain leaves, so adding or removing a path
+    /// changes only the group that contains it. Useful when diffing outputs
+    /// across runs; see also [`brace_paths_with_hint`].
+    ///
+    /// # Examples
+    /// When `true`:
+    /// - `["src/a.rs", "src/b.rs", "README.md"]` → `"{src/{a,b}.rs,README.md}"`
+    ///
+    /// When `false`:
+    /// - `["src/a.rs", "src/b.rs", "README.md"]` → `"{src/{a.rs,b.rs},README.md}"`
+    pub stable_output: bool,
+
     /// Highlight braces with colors (default: `false`).
     /// Only available with the `highlight` feature enabled.
     #[cfg(feature = "highlight")]
@@ -140,6 +316,8 @@ impl Default for BraceConfig {
     fn default() -> Self {
         Self {
             path_separator: "/".to_string(),
+            path_style: PathStyle::Posix,
+            separators: vec!["/".to_string(), "\\".to_string()],
             max_depth: 5,
             max_brace_size: None,
             allow_stem_split: false,
@@ -149,7 +327,16 @@ impl Default for BraceConfig {
             preserve_order_within_braces: false,
             allow_mixed_separators: false,
             deduplicate_inputs: true,
+            direction: Direction::LeftToRight,
+            normalisation: Normalisation::default(),
+            base: None,
+            outside_base: OutsideBase::Reject,
+            base_display: BaseDisplay::Hide,
             reprocess_braces: false,
+            escape: EscapePolicy::Reject,
+            case_folding: CaseFolding::Off,
+            verify: VerifyMode::Off,
+            stable_output: false,
             #[cfg(feature = "highlight")]
             highlight: false,
         }
@@ -158,6 +345,119 @@ impl Default for BraceConfig {
 
 /// Public entry: expand paths into braces
 pub fn brace_paths(paths: &[impl AsRef<str>], config: &BraceConfig) -> Result<String> {
+    let config = &resolve_style(config);
+    let expr = brace_paths_expr(paths, config)?;
+    Ok(finish_output(&expr, config))
+}
+
+/// Expand paths into braces, returning the expression as a [`BraceExpr`] tree
+///
+/// The tree writes the same string as [`brace_paths`] (without highlighting),
+/// for callers that walk the structure instead of parsing the text back.
+pub fn brace_paths_expr(paths: &[impl AsRef<str>], config: &BraceConfig) -> Result<BraceExpr> {
+    let config = &resolve_style(config);
+    let paths = prepare_paths(paths, config)?;
+
+    render_verified(&paths, config)
+}
+
+/// Expand paths into braces, splitting them into segments with `segmenter`
+///
+/// Each path is validated and normalised as by [`brace_paths`], then split
+/// by the segmenter in place of `config.path_separator`, and segments are
+/// joined with [`Segmenter::separator`] in the output. This compresses
+/// things other than file paths, such as class or metric names.
+///
+/// # Example
+/// ```
+/// use braces::{brace_paths_with, BraceConfig, SeparatorSegmenter};
+///
+/// let metrics = vec!["http.server.requests.count", "http.server.requests.sum"];
+/// let segmenter = SeparatorSegmenter::new(".");
+/// let result = brace_paths_with(&metrics, &BraceConfig::default(), &segmenter).unwrap();
+/// assert_eq!(result, "http.server.requests.{count,sum}");
+/// ```
+pub fn brace_paths_with(
+    paths: &[impl AsRef<str>],
+    config: &BraceConfig,
+    segmenter: &dyn Segmenter,
+) -> Result<String> {
+    let config = &resolve_style(config);
+    let paths = prepare_paths(paths, config)?;
+
+    let expr = verify_output(
+        render_paths(&paths, config, segmenter),
+        &displayed_paths(&paths, config),
+        config,
+    )?;
+    Ok(finish_output(&expr, config))
+}
+
+/// Expand paths into braces, keeping the item order of a previous output
+///
+/// Paths that appear in `previous` (typically the output of an earlier run)
+/// keep their relative order from it, and new paths follow in input order.
+/// Combined with `stable_output`, this keeps the diff between successive
+/// outputs confined to the groups whose contents actually changed.
+///
+/// # Example
+/// ```
+/// use braces::{brace_paths_with_hint, BraceConfig};
+///
+/// let config = BraceConfig {
+///     stable_output: true,
+///     ..Default::default()
+/// };
+/// let paths = vec!["src/c.rs", "src/a.rs", "src/b.rs"];
+/// let result = brace_paths_with_hint(&paths, &config, "src/{a,c}.rs").unwrap();
+/// assert_eq!(result, "src/{a,c,b}.rs");
+/// ```
+pub fn brace_paths_with_hint(
+    paths: &[impl AsRef<str>],
+    config: &BraceConfig,
+    previous: &str,
+) -> Result<String> {
+    let config = &resolve_style(config);
+    let paths = prepare_paths(paths, config)?;
+
+    let mut positions = HashMap::new();
+    for (pos, path) in expand_braces_with(previous, config.escape)
+        .into_iter()
+        .enumerate()
+    {
+        positions.entry(path).or_insert(pos);
+    }
+
+    // Stable sort: new paths (no position) keep their input order after the rest
+    let mut ordered = paths;
+    ordered.sort_by_key(|p| positions.get(p).copied().unwrap_or(usize::MAX));
+
+    let expr = render_verified(&ordered, config)?;
+    Ok(finish_output(&expr, config))
+}
+
+/// Write an expression as output, highlighted if configured
+pub(crate) fn finish_output(expr: &BraceExpr, config: &BraceConfig) -> String {
+    expr.render(output_renderer(config), config.escape)
+}
+
+/// The renderer that writes output, [`BraceRenderer`] or the highlighting one
+pub(crate) fn output_renderer(config: &BraceConfig) -> &'static dyn Renderer {
+    #[cfg(all(feature = "cli", feature = "highlight"))]
+    if config.highlight {
+        return &crate::highlight::AnsiRenderer;
+    }
+    #[cfg(not(all(feature = "cli", feature = "highlight")))]
+    let _ = config;
+
+    &BraceRenderer
+}
+
+/// Validate, expand and deduplicate input paths ahead of building the trie
+pub(crate) fn prepare_paths(
+    paths: &[impl AsRef<str>],
+    config: &BraceConfig,
+) -> Result<Vec<String>> {
     if paths.is_empty() {
         return Err(BraceError::EmptyInput);
     }
@@ -165,18 +465,12 @@ pub fn brace_paths(paths: &[impl AsRef<str>], config: &BraceConfig) -> Result<St
     // Convert to owned strings
     let mut paths: Vec<String> = paths.iter().map(|p| p.as_ref().to_string()).collect();
 
-    // Normalize separators
-    if !config.allow_mixed_separators {
-        validate_separators(&paths, &config.path_separator)?;
-    } else {
-        paths = paths
-            .into_iter()
-            .map(|p| normalise_separators(&p, &config.path_separator))
-            .collect();
-    }
-
     // Handle braces in input if reprocess disabled
-    if !config.reprocess_braces && paths.iter().any(|p| p.contains('{') || p.contains('}')) {
+    let rejecting = config.escape == EscapePolicy::Reject;
+    if rejecting
+        && !config.reprocess_braces
+        && paths.iter().any(|p| p.contains('{') || p.contains('}'))
+    {
         return Err(BraceError::InvalidBraceInput {
             path: paths
                 .iter()
@@ -188,7 +482,38 @@ pub fn brace_paths(paths: &[impl AsRef<str>], config: &BraceConfig) -> Result<St
     }
 
     if config.reprocess_braces {
-        paths = paths.into_iter().flat_map(|p| expand_braces(&p)).collect();
+        paths = paths
+            .into_iter()
+            .flat_map(|p| expand_braces_with(&p, config.escape))
+            .collect();
+    }
+
+    // Normalize separators
+    if !config.allow_mixed_separators {
+        validate_separators(&paths, &config.path_separator, &config.separators)?;
+    } else {
+        paths = paths
+            .into_iter()
+            .map(|p| normalise_separators(&p, &config.path_separator, &config.separators))
+            .collect();
+    }
+
+    // Lexically normalise `.`, `..` and repeated separators
+    paths = paths
+        .into_iter()
+        .map(|p| normalise_path(&p, config))
+        .collect();
+
+    check_base(&paths, config)?;
+
+    // Any metacharacters left would be confused with brace syntax
+    if rejecting {
+        if let Some(path) = paths.iter().find(|p| p.contains(REJECTED)) {
+            return Err(BraceError::InvalidBraceInput {
+                path: path.clone(),
+                reason: "contains brace metacharacters and no escape policy is set".to_string(),
+            });
+        }
     }
 
     // Deduplicate while preserving order (only if enabled)
@@ -196,38 +521,123 @@ pub fn brace_paths(paths: &[impl AsRef<str>], config: &BraceConfig) -> Result<St
         let mut seen = HashSet::new();
         let mut ordered = Vec::with_capacity(paths.len());
         for p in paths.into_iter() {
-            if seen.insert(p.clone()) {
+            if seen.insert(config.case_folding.fold(&p)) {
                 ordered.push(p);
             }
         }
         paths = ordered;
     }
 
-    // Strip common suffix for cleaner braces
-    let common_suffix = find_common_suffix(&paths);
-    let stripped_paths: Vec<String> = if !common_suffix.is_empty() {
+    Ok(paths)
+}
+
+/// Strip the common suffix shared by all paths, returning it with the stripped paths
+///
+/// Right to left, the common prefix is stripped instead, as the trie groups
+/// the shared suffix. Nothing is stripped in `stable_output` mode, where
+/// factoring must stay local.
+fn strip_common_suffix(paths: &[String], config: &BraceConfig) -> (String, Vec<String>) {
+    let common_suffix = if config.stable_output {
+        String::new()
+    } else if config.direction == Direction::RightToLeft {
+        let reversed: Vec<String> = paths.iter().map(|p| p.chars().rev().collect()).collect();
+        find_common_suffix(&reversed).chars().rev().collect()
+    } else {
+        find_common_suffix(paths)
+    };
+    let stripped_paths: Vec<String> = if common_suffix.is_empty() {
+        paths.to_vec()
+    } else if config.direction == Direction::RightToLeft {
         paths
             .iter()
-            .map(|s| s.strip_suffix(&common_suffix).unwrap_or(s).to_string())
+            .map(|s| s.strip_prefix(&common_suffix).unwrap_or(s).to_string())
             .collect()
     } else {
-        paths.clone()
+        paths
+            .iter()
+            .map(|s| s.strip_suffix(&common_suffix).unwrap_or(s).to_string())
+            .collect()
     };
+    (common_suffix, stripped_paths)
+}
 
-    let (nodes, root_idx) = build_trie(&stripped_paths, &config.path_separator, config);
+/// Compress prepared paths, checking the result according to `config.verify`
+pub(crate) fn render_verified(paths: &[String], config: &BraceConfig) -> Result<BraceExpr> {
+    let segmenter = PathSegmenter::new(&config.path_separator, config);
+    verify_output(
+        render_paths(paths, config, &segmenter),
+        &displayed_paths(paths, config),
+        config,
+    )
+}
 
-    // Compute representations
-    let (reprs, _) = compute_reprs(&nodes, root_idx, &config.path_separator, config);
+/// Compress prepared paths into brace syntax (without highlighting)
+///
+/// With a `base` directory, paths under it are rendered relative to it and
+/// any paths kept outside it follow in a separate group.
+pub(crate) fn render_paths(
+    paths: &[String],
+    config: &BraceConfig,
+    segmenter: &dyn Segmenter,
+) -> BraceExpr {
+    let Some(base) = &config.base else {
+        return render_group(paths, config, segmenter);
+    };
 
-    let mut result = reprs.get(&root_idx).cloned().unwrap_or_default();
-    if !common_suffix.is_empty() {
-        result.push_str(&common_suffix);
+    let (inside, outside) = split_base(paths, base, config);
+    let mut groups = vec![];
+    if !inside.is_empty() {
+        let rendered = render_group(&inside, config, segmenter);
+        groups.push(BraceExpr::concat([
+            BraceExpr::Literal(base_prefix(config, true)),
+            rendered,
+        ]));
+    }
+    if !outside.is_empty() {
+        groups.push(render_group(&outside, config, segmenter));
+    }
+    if groups.len() == 1 {
+        groups.remove(0)
+    } else {
+        BraceExpr::Alt(groups)
     }
+}
 
-    #[cfg(feature = "cli")]
-    if config.highlight {
-        result = crate::highlight::highlight_braces(&result);
+/// Compress a group of paths into brace syntax
+fn render_group(paths: &[String], config: &BraceConfig, segmenter: &dyn Segmenter) -> BraceExpr {
+    // A root shared by every path is kept in front, so that `/` is not lost
+    let sep = segmenter.separator();
+    if paths.len() > 1 && !sep.is_empty() && paths.iter().all(|p| p.starts_with(sep) && p != sep) {
+        let relative: Vec<String> = paths.iter().map(|p| p[sep.len()..].to_string()).collect();
+        return BraceExpr::concat([
+            BraceExpr::Literal(escape_text(sep, config.escape)),
+            render_group(&relative, config, segmenter),
+        ]);
     }
 
-    Ok(result)
+    // Strip common suffix for cleaner braces
+    let (common_suffix, stripped_paths) = strip_common_suffix(paths, config);
+
+    let (nodes, root_idx) = build_trie_with(&stripped_paths, segmenter, config);
+
+    render_trie_expr(&nodes, root_idx, &common_suffix, sep, config)
+}
+
+/// Render a built trie as an expression, joining segments with `sep` and re-attaching the stripped common suffix
+pub(crate) fn render_trie_expr(
+    nodes: &[trie::Node],
+    root_idx: usize,
+    common_suffix: &str,
+    sep: &str,
+    config: &BraceConfig,
+) -> BraceExpr {
+    // Compute representations
+    let (mut reprs, _) = compute_reprs(nodes, root_idx, sep, config);
+
+    let result = reprs.remove(&root_idx).unwrap_or_else(BraceExpr::empty);
+    let common = BraceExpr::Literal(escape_text(common_suffix, config.escape));
+    match config.direction {
+        Direction::LeftToRight => BraceExpr::concat([result, common]),
+        Direction::RightToLeft => BraceExpr::concat([common, result]),
+    }
 }
@@ -0,0 +1,290 @@
+//! Union, intersection and difference of path sets, re-compressed into braces
+
+use super::ast::BraceExpr;
+use super::canonical::parse;
+use super::escape::{escape_text, REJECTED};
+use super::matcher::BraceMatcher;
+use super::parse::{SpannedExpr, SpannedKind};
+use super::trie::build_trie;
+use super::{
+    finish_output, prepare_paths, render_verified, resolve_style, BraceConfig, EscapePolicy,
+};
+use crate::error::{BraceError, Result};
+
+/// A set operation combining two path sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOperation {
+    /// Paths in either set.
+    Union,
+
+    /// Paths in both sets.
+    Intersection,
+
+    /// Paths in the first set but not the second.
+    Difference,
+}
+
+impl SetOperation {
+    /// Whether a path is in the result, given its membership of each set
+    fn keeps(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            SetOperation::Union => in_a || in_b,
+            SetOperation::Intersection => in_a && in_b,
+            SetOperation::Difference => in_a && !in_b,
+        }
+    }
+}
+
+/// Combine two path sets and compress the result into braces
+///
+/// Both sets are prepared as by [`brace_paths`](crate::brace_paths), so paths
+/// match after normalisation and case folding as configured, and are merged
+/// into one trie whose leaves record which set they came from. The paths kept
+/// by `op` are then compressed with `config`, in the order they first appear
+/// in `a` followed by `b`. Returns `None` when the result is empty.
+///
+/// # Example
+/// ```
+/// use braces::{combine_paths, BraceConfig, SetOperation};
+///
+/// let config = BraceConfig::default();
+/// let a = vec!["src/a.rs", "src/b.rs", "src/c.rs"];
+/// let b = vec!["src/b.rs", "src/c.rs", "src/d.rs"];
+/// let both = combine_paths(&a, &b, SetOperation::Intersection, &config).unwrap();
+/// assert_eq!(both.as_deref(), Some("src/{b,c}.rs"));
+/// let left = combine_paths(&a, &b, SetOperation::Difference, &config).unwrap();
+/// assert_eq!(left.as_deref(), Some("src/a.rs"));
+/// ```
+pub fn combine_paths(
+    a: &[impl AsRef<str>],
+    b: &[impl AsRef<str>],
+    op: SetOperation,
+    config: &BraceConfig,
+) -> Result<Option<String>> {
+    let config = &resolve_style(config);
+    let a = prepare_set(a, config)?;
+    let b = prepare_set(b, config)?;
+
+    let combined: Vec<String> = a.iter().chain(&b).cloned().collect();
+    let kept = kept_paths(&combined, a.len(), op, config);
+    if kept.is_empty() {
+        return Ok(None);
+    }
+
+    let expr = render_verified(&kept, config)?;
+    Ok(Some(finish_output(&expr, config)))
+}
+
+/// Combine the path sets of two brace expressions and compress the result
+///
+/// Both expressions are read with `config.escape`, and neither is expanded
+/// in full. The union is written as the group `{a,b}`, with the text the two
+/// share at either end factored out. For an intersection the operand with
+/// fewer paths is expanded and each of its paths tested against the other,
+/// and for a difference the paths of `a` are tested against `b`; the paths
+/// kept are then compressed as by [`combine_paths`]. Paths are compared as
+/// written, folding case as configured, and are not normalised. Returns
+/// [`BraceError::InvalidBraceSyntax`](crate::BraceError::InvalidBraceSyntax)
+/// if either cannot be parsed, and `None` when the result is empty.
+///
+/// # Example
+/// ```
+/// use braces::{combine_braces, BraceConfig, SetOperation};
+///
+/// let config = BraceConfig::default();
+/// let result = combine_braces("src/{a,b}.rs", "src/{b,c}.rs", SetOperation::Union, &config);
+/// assert_eq!(result.unwrap().as_deref(), Some("src/{a,b,c}.rs"));
+/// let result = combine_braces("log/{1..100000}.txt", "log/{7,x}.txt", SetOperation::Intersection, &config);
+/// assert_eq!(result.unwrap().as_deref(), Some("log/7.txt"));
+/// ```
+pub fn combine_braces(
+    a: &str,
+    b: &str,
+    op: SetOperation,
+    config: &BraceConfig,
+) -> Result<Option<String>> {
+    let config = &resolve_style(config);
+    let (parsed_a, parsed_b) = (parse(a, config)?, parse(b, config)?);
+
+    let kept: Vec<String> = match op {
+        SetOperation::Union => {
+            let expr = union(
+                written(&parsed_a, a, config)?,
+                written(&parsed_b, b, config)?,
+                config.escape,
+            );
+            return Ok((!expr.is_empty()).then(|| finish_output(&expr, config)));
+        }
+        SetOperation::Intersection if path_count(&parsed_b) < path_count(&parsed_a) => {
+            let matcher = BraceMatcher::new(a, config)?;
+            parsed_b
+                .expand()
+                .into_iter()
+                .filter(|p| matcher.is_match(p))
+                .collect()
+        }
+        SetOperation::Intersection => {
+            let matcher = BraceMatcher::new(b, config)?;
+            parsed_a
+                .expand()
+                .into_iter()
+                .filter(|p| matcher.is_match(p))
+                .collect()
+        }
+        SetOperation::Difference => {
+            let matcher = BraceMatcher::new(b, config)?;
+            parsed_a
+                .expand()
+                .into_iter()
+                .filter(|p| !matcher.is_match(p))
+                .collect()
+        }
+    };
+    if kept.is_empty() {
+        return Ok(None);
+    }
+    let expr = render_verified(&prepare_paths(&kept, config)?, config)?;
+    Ok(Some(finish_output(&expr, config)))
+}
+
+/// The number of paths an expression stands for, saturating at `usize::MAX`
+fn path_count(expr: &SpannedExpr) -> usize {
+    match &expr.kind {
+        SpannedKind::Literal(_) | SpannedKind::Trivia => 1,
+        SpannedKind::Concat(parts) => parts
+            .iter()
+            .fold(1, |count, part| count.saturating_mul(path_count(part))),
+        SpannedKind::Alt(options) => options
+            .iter()
+            .fold(0, |count, option| count.saturating_add(path_count(option))),
+        SpannedKind::Range(range) => range.expansion_count(),
+    }
+}
+
+/// Convert a parsed expression for output, keeping sequences as written so they are not expanded
+///
+/// Literal text is escaped again with `config.escape`, as it may end up inside
+/// a group; under [`EscapePolicy::Reject`] text that cannot be written there is
+/// rejected.
+fn written(expr: &SpannedExpr, source: &str, config: &BraceConfig) -> Result<BraceExpr> {
+    Ok(match &expr.kind {
+        SpannedKind::Literal(text) => {
+            if config.escape == EscapePolicy::Reject && text.contains(REJECTED) {
+                return Err(BraceError::InvalidBraceInput {
+                    path: source.to_string(),
+                    reason: "contains brace metacharacters and no escape policy is set".to_string(),
+                });
+            }
+            BraceExpr::Literal(escape_text(text, config.escape))
+        }
+        SpannedKind::Trivia => BraceExpr::empty(),
+        SpannedKind::Concat(parts) => BraceExpr::concat(
+            parts
+                .iter()
+                .map(|part| written(part, source, config))
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        SpannedKind::Alt(options) => BraceExpr::Alt(
+            options
+                .iter()
+                .map(|option| written(option, source, config))
+                .collect::<Result<_>>()?,
+        ),
+        SpannedKind::Range(_) => BraceExpr::Literal(source[expr.span.clone()].to_string()),
+    })
+}
+
+/// The group `{a,b}`, with the atoms both start and end with factored out
+///
+/// A side that is itself a group contributes its alternatives, and repeated
+/// alternatives are written once.
+fn union(a: BraceExpr, b: BraceExpr, policy: EscapePolicy) -> BraceExpr {
+    let (a, b) = (a.atoms(policy), b.atoms(policy));
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let (shared, a, b) = (&a[..prefix], &a[prefix..], &b[prefix..]);
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let mut options: Vec<BraceExpr> = vec![];
+    for rest in [&a[..a.len() - suffix], &b[..b.len() - suffix]] {
+        let rest = BraceExpr::concat(rest.iter().cloned());
+        let rest = match rest {
+            BraceExpr::Alt(inner) => inner,
+            other => vec![other],
+        };
+        for option in rest {
+            if !options.contains(&option) {
+                options.push(option);
+            }
+        }
+    }
+    let middle = match options.len() {
+        1 => options.remove(0),
+        _ => BraceExpr::Alt(options),
+    };
+    BraceExpr::concat(
+        shared
+            .iter()
+            .cloned()
+            .chain([middle])
+            .chain(a[a.len() - suffix..].iter().cloned()),
+    )
+}
+
+/// Prepare one operand, which unlike the input to `brace_paths` may be empty
+pub(crate) fn prepare_set(paths: &[impl AsRef<str>], config: &BraceConfig) -> Result<Vec<String>> {
+    if paths.is_empty() {
+        Ok(vec![])
+    } else {
+        prepare_paths(paths, config)
+    }
+}
+
+/// Paths of `combined` kept by `op`, where the first `split` paths form the first set
+fn kept_paths(
+    combined: &[String],
+    split: usize,
+    op: SetOperation,
+    config: &BraceConfig,
+) -> Vec<String> {
+    memberships(combined, split, config)
+        .into_iter()
+        .filter(|&(_, in_a, in_b)| op.keeps(in_a, in_b))
+        .map(|(i, _, _)| combined[i].clone())
+        .collect()
+}
+
+/// Membership of each distinct path in the two sets, as `(index, in_a, in_b)`
+///
+/// The first `split` paths of `combined` form the first set. Each trie leaf
+/// holds the paths that match it, so membership of both sets is read off the
+/// leaves, and each leaf is represented by its first path. Returned in order
+/// of those first paths.
+pub(crate) fn memberships(
+    combined: &[String],
+    split: usize,
+    config: &BraceConfig,
+) -> Vec<(usize, bool, bool)> {
+    let trie_config = BraceConfig {
+        deduplicate_inputs: true,
+        ..config.clone()
+    };
+    let (nodes, _) = build_trie(combined, &config.path_separator, &trie_config);
+
+    let mut leaves: Vec<(usize, bool, bool)> = nodes
+        .iter()
+        .filter(|node| node.is_leaf)
+        .filter_map(|node| {
+            let first = node.sources.iter().min().copied()?;
+            let in_a = node.sources.iter().any(|&s| s < split);
+            let in_b = node.sources.iter().any(|&s| s >= split);
+            Some((first, in_a, in_b))
+        })
+        .collect();
+    leaves.sort_unstable();
+    leaves
+}
@@ -0,0 +1,148 @@
+//! Brace expressions as a tree, the structured result of compression
+
+use super::escape::{atoms, EscapePolicy};
+use super::render::BraceRenderer;
+use std::fmt;
+
+/// A brace expression: literal text, concatenation and alternation
+///
+/// This is the structure behind the string returned by
+/// [`brace_paths`](crate::brace_paths), and its [`Display`](fmt::Display)
+/// impl writes exactly that string, with [`BraceRenderer`]. Other syntaxes
+/// can be written with [`render`](Self::render). Literal text is held as
+/// written in the output, so it includes any escaping applied by the
+/// [`EscapePolicy`].
+///
+/// # Example
+/// ```
+/// use braces::{brace_paths_expr, BraceConfig, BraceExpr};
+///
+/// let expr = brace_paths_expr(&["a/b.rs", "a/c.rs"], &BraceConfig::default()).unwrap();
+/// assert_eq!(
+///     expr,
+///     BraceExpr::Concat(vec![
+///         BraceExpr::Literal("a/".into()),
+///         BraceExpr::Alt(vec![BraceExpr::Literal("b".into()), BraceExpr::Literal("c".into())]),
+///         BraceExpr::Literal(".rs".into()),
+///     ])
+/// );
+/// assert_eq!(expr.to_string(), "a/{b,c}.rs");
+/// assert_eq!(expr.expansion_count(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BraceExpr {
+    /// Literal text, as written in the output.
+    Literal(String),
+
+    /// Parts written one after another.
+    Concat(Vec<BraceExpr>),
+
+    /// Alternatives, written `{a,b,c}`.
+    Alt(Vec<BraceExpr>),
+}
+
+impl BraceExpr {
+    /// The empty expression, which expands to one empty string
+    pub fn empty() -> Self {
+        BraceExpr::Literal(String::new())
+    }
+
+    /// Concatenate parts, flattening nested concatenations and merging literals
+    pub fn concat(parts: impl IntoIterator<Item = BraceExpr>) -> Self {
+        let mut out: Vec<BraceExpr> = vec![];
+        for part in parts {
+            let flattened = match part {
+                BraceExpr::Concat(inner) => inner,
+                other => vec![other],
+            };
+            for part in flattened {
+                match (out.last_mut(), part) {
+                    (_, BraceExpr::Literal(text)) if text.is_empty() => {}
+                    (Some(BraceExpr::Literal(prev)), BraceExpr::Literal(text)) => {
+                        prev.push_str(&text)
+                    }
+                    (_, part) => out.push(part),
+                }
+            }
+        }
+        match out.len() {
+            0 => BraceExpr::empty(),
+            1 => out.pop().unwrap(),
+            _ => BraceExpr::Concat(out),
+        }
+    }
+
+    /// Whether the expression writes nothing at all
+    pub fn is_empty(&self) -> bool {
+        match self {
+            BraceExpr::Literal(text) => text.is_empty(),
+            BraceExpr::Concat(parts) => parts.iter().all(BraceExpr::is_empty),
+            BraceExpr::Alt(_) => false,
+        }
+    }
+
+    /// Number of strings the expression expands to
+    pub fn expansion_count(&self) -> usize {
+        match self {
+            BraceExpr::Literal(_) => 1,
+            BraceExpr::Concat(parts) => parts.iter().map(BraceExpr::expansion_count).product(),
+            BraceExpr::Alt(options) => options.iter().map(BraceExpr::expansion_count).sum(),
+        }
+    }
+
+    /// The same expression with each literal, as written, replaced by `f`
+    pub(crate) fn map_literals(&self, f: &dyn Fn(&str) -> String) -> BraceExpr {
+        match self {
+            BraceExpr::Literal(text) => BraceExpr::Literal(f(text)),
+            BraceExpr::Concat(parts) => {
+                BraceExpr::Concat(parts.iter().map(|p| p.map_literals(f)).collect())
+            }
+            BraceExpr::Alt(options) => {
+                BraceExpr::Alt(options.iter().map(|o| o.map_literals(f)).collect())
+            }
+        }
+    }
+
+    /// The subexpression at `address`, which lists the index of the part or
+    /// alternative taken at each level
+    pub(crate) fn at(&self, address: &[usize]) -> &BraceExpr {
+        address.iter().fold(self, |expr, &i| match expr {
+            BraceExpr::Concat(parts) | BraceExpr::Alt(parts) => &parts[i],
+            BraceExpr::Literal(_) => expr,
+        })
+    }
+
+    /// The subexpression at `address`, for replacing
+    pub(crate) fn at_mut(&mut self, address: &[usize]) -> &mut BraceExpr {
+        let mut expr = self;
+        for &i in address {
+            expr = match expr {
+                BraceExpr::Concat(parts) | BraceExpr::Alt(parts) => &mut parts[i],
+                BraceExpr::Literal(_) => return expr,
+            };
+        }
+        expr
+    }
+
+    /// Split into the same atoms as [`atoms`] finds in the written text
+    ///
+    /// Literal text is split into characters, escapes and quoted runs, while
+    /// each alternation stays whole.
+    pub(crate) fn atoms(&self, policy: EscapePolicy) -> Vec<BraceExpr> {
+        match self {
+            BraceExpr::Literal(text) => atoms(text, policy)
+                .into_iter()
+                .map(BraceExpr::Literal)
+                .collect(),
+            BraceExpr::Concat(parts) => parts.iter().flat_map(|p| p.atoms(policy)).collect(),
+            BraceExpr::Alt(_) => vec![self.clone()],
+        }
+    }
+}
+
+impl fmt::Display for BraceExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Literals are written as they are held, so no unescaping is needed
+        f.write_str(&self.render(&BraceRenderer, EscapePolicy::Reject))
+    }
+}
@@ -0,0 +1,176 @@
+//! Making paths relative to a base directory
+
+use super::escape::escape_text;
+use super::{BraceConfig, CaseFolding};
+use crate::error::{BraceError, Result};
+use std::collections::HashSet;
+
+/// What to do with paths that are not under the `base` directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutsideBase {
+    /// Return [`BraceError::OutsideBase`] (default).
+    #[default]
+    Reject,
+
+    /// Keep them as written, in a separate group after the paths under the base.
+    KeepAbsolute,
+}
+
+/// How the `base` directory is shown in front of the relative paths
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum BaseDisplay {
+    /// Leave it out, so the output is relative to the base (default).
+    #[default]
+    Hide,
+
+    /// Re-attach it as a literal prefix: `/home/ci/repo/src/{a,b}.rs`.
+    Prefix,
+
+    /// Replace it with a variable, written verbatim: `$ROOT/src/{a,b}.rs`.
+    Variable(String),
+}
+
+/// The base directory without trailing separators (a lone root is kept)
+fn trimmed_base<'a>(base: &'a str, sep: &str) -> &'a str {
+    let mut trimmed = base;
+    while !sep.is_empty() && trimmed.len() > sep.len() && trimmed.ends_with(sep) {
+        trimmed = &trimmed[..trimmed.len() - sep.len()];
+    }
+    trimmed
+}
+
+/// The path relative to `base`, if it is the base or lies under it
+///
+/// The base itself is written `.`, and matching is on whole segments, so
+/// `/srv/app2` is not under `/srv/app`. Case is compared as configured by
+/// `case_folding`.
+pub(crate) fn relative_to<'a>(path: &'a str, base: &str, config: &BraceConfig) -> Option<&'a str> {
+    let sep = config.path_separator.as_str();
+    let base = trimmed_base(base, sep);
+    let rest = strip_prefix_folded(path, base, config.case_folding)?;
+    if rest.is_empty() || rest == sep {
+        return Some(".");
+    }
+    if base.ends_with(sep) {
+        return Some(rest);
+    }
+    rest.strip_prefix(sep).filter(|r| !r.is_empty())
+}
+
+/// Strip `prefix` from `text`, comparing characters as folded by `case_folding`
+fn strip_prefix_folded<'a>(
+    text: &'a str,
+    prefix: &str,
+    case_folding: CaseFolding,
+) -> Option<&'a str> {
+    if case_folding == CaseFolding::Off {
+        return text.strip_prefix(prefix);
+    }
+    let mut chars = text.char_indices();
+    for expected in prefix.chars() {
+        let (_, c) = chars.next()?;
+        if !c.to_lowercase().eq(expected.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(chars.as_str())
+}
+
+/// Reject paths outside the base directory, unless they are to be kept
+///
+/// Kept paths are rejected if one is spelled the same as a path under the
+/// base is shown, as the output could not tell the two apart.
+pub(crate) fn check_base(paths: &[String], config: &BraceConfig) -> Result<()> {
+    let Some(base) = &config.base else {
+        return Ok(());
+    };
+    match config.outside_base {
+        OutsideBase::Reject => {
+            if let Some(path) = paths
+                .iter()
+                .find(|p| relative_to(p, base, config).is_none())
+            {
+                return Err(BraceError::OutsideBase {
+                    path: path.clone(),
+                    base: base.clone(),
+                });
+            }
+        }
+        OutsideBase::KeepAbsolute => {
+            let prefix = base_prefix(config, false);
+            let fold = |p: &str| config.case_folding.fold(p);
+            let shown: HashSet<String> = paths
+                .iter()
+                .filter_map(|p| relative_to(p, base, config))
+                .map(|rel| fold(&format!("{}{}", prefix, rel)))
+                .collect();
+            if let Some(path) = paths
+                .iter()
+                .find(|p| relative_to(p, base, config).is_none() && shown.contains(&fold(p)))
+            {
+                return Err(BraceError::InvalidBraceInput {
+                    path: path.clone(),
+                    reason: format!(
+                        "outside the base directory '{}', but shown the same as a path under it",
+                        base
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Split paths into those under the base (made relative) and those outside it
+pub(crate) fn split_base(
+    paths: &[String],
+    base: &str,
+    config: &BraceConfig,
+) -> (Vec<String>, Vec<String>) {
+    let mut inside = vec![];
+    let mut outside = vec![];
+    for path in paths {
+        match relative_to(path, base, config) {
+            Some(rel) => inside.push(rel.to_string()),
+            None => outside.push(path.clone()),
+        }
+    }
+    (inside, outside)
+}
+
+/// Prefix written in front of the relative paths, escaped for output if `escaped`
+pub(crate) fn base_prefix(config: &BraceConfig, escaped: bool) -> String {
+    let Some(base) = &config.base else {
+        return String::new();
+    };
+    let sep = &config.path_separator;
+    let base = trimmed_base(base, sep);
+    let joined = |prefix: String| {
+        if prefix.ends_with(sep.as_str()) {
+            prefix
+        } else {
+            format!("{}{}", prefix, sep)
+        }
+    };
+    match &config.base_display {
+        BaseDisplay::Hide => String::new(),
+        BaseDisplay::Prefix if escaped => joined(escape_text(base, config.escape)),
+        BaseDisplay::Prefix => joined(base.to_string()),
+        BaseDisplay::Variable(var) => joined(var.clone()),
+    }
+}
+
+/// The paths as the output spells them, for round-trip verification
+pub(crate) fn displayed_paths(paths: &[String], config: &BraceConfig) -> Vec<String> {
+    let Some(base) = &config.base else {
+        return paths.to_vec();
+    };
+    let prefix = base_prefix(config, false);
+    paths
+        .iter()
+        .map(|p| match relative_to(p, base, config) {
+            Some(rel) => format!("{}{}", prefix, rel),
+            None => p.clone(),
+        })
+        .collect()
+}
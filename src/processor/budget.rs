@@ -0,0 +1,196 @@
+//! Fit braces output into a length budget by summarising subtrees
+
+use super::ast::BraceExpr;
+use super::{finish_output, prepare_paths, render_verified, resolve_style, BraceConfig};
+use crate::error::Result;
+use std::collections::HashSet;
+
+/// Braces output produced under a length budget
+///
+/// When the full expression does not fit, subtrees are summarised as
+/// `src/{…14 files}` and long groups are truncated as `tests/{a,b,…+37}`.
+/// Such output is marked `lossy` and cannot be expanded back into the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetedBraces {
+    /// The braces expression, summarised if needed to fit the budget.
+    pub text: String,
+
+    /// Whether any paths were elided from `text`.
+    pub lossy: bool,
+}
+
+/// Expand paths into braces, summarising subtrees to fit within `budget` characters
+///
+/// The paths are compressed as by [`brace_paths`](crate::brace_paths), then
+/// groups are collapsed deepest first (largest first within a level), so
+/// the top of the hierarchy is kept for as long as possible. If collapsing
+/// every inner group is still too long, the outermost group is truncated,
+/// and as a last resort the whole set is summarised. The budget is measured in
+/// characters, before any highlighting, and may still be exceeded when even
+/// the fully summarised form does not fit.
+///
+/// # Example
+/// ```
+/// use braces::{brace_paths_budgeted, BraceConfig};
+///
+/// let paths = vec!["src/a.rs", "src/b.rs", "src/c.rs", "README.md"];
+/// let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 26).unwrap();
+/// assert_eq!(result.text, "{src/{…3 files},README.md}");
+/// assert!(result.lossy);
+/// ```
+pub fn brace_paths_budgeted(
+    paths: &[impl AsRef<str>],
+    config: &BraceConfig,
+    budget: usize,
+) -> Result<BudgetedBraces> {
+    let config = &resolve_style(config);
+    let paths = prepare_paths(paths, config)?;
+    let full = render_verified(&paths, config)?;
+    // Counts are taken from the full expression, as elided groups count as one
+    let count = |address: &[usize]| full.at(address).expansion_count();
+    let mut expr = full.clone();
+
+    let mut text = expr.to_string();
+    let mut lossy = false;
+
+    // Collapse groups deepest first until the output fits, keeping the outermost
+    let mut visited = HashSet::new();
+    while char_len(&text) > budget {
+        let candidates: Vec<(Vec<usize>, usize)> = groups(&expr)
+            .into_iter()
+            .filter(|(address, depth)| *depth > 0 && !visited.contains(address))
+            .collect();
+        let Some(deepest) = candidates.iter().map(|(_, depth)| *depth).max() else {
+            break;
+        };
+
+        let mut level: Vec<(Vec<usize>, usize, usize)> = candidates
+            .into_iter()
+            .filter(|(_, depth)| *depth == deepest)
+            .map(|(address, _)| {
+                let count = count(&address);
+                let current = char_len(&expr.at(&address).to_string());
+                let saving = current.saturating_sub(char_len(&files_summary(count).to_string()));
+                (address, count, saving)
+            })
+            .collect();
+        level.sort_by_key(|(_, _, saving)| std::cmp::Reverse(*saving));
+
+        let mut estimate = char_len(&text);
+        for (address, count, saving) in level {
+            visited.insert(address.clone());
+            if saving == 0 {
+                // Summary would be no shorter than the group it replaces
+                continue;
+            }
+            *expr.at_mut(&address) = files_summary(count);
+            lossy = true;
+            estimate = estimate.saturating_sub(saving);
+            if estimate <= budget {
+                break;
+            }
+        }
+        text = expr.to_string();
+    }
+
+    if char_len(&text) > budget {
+        lossy = true;
+        expr = truncate_outermost(&expr, &count, budget)
+            // The suffix belongs to the elided paths, so is summarised with them
+            .unwrap_or_else(|| files_summary(count(&[])));
+    }
+
+    Ok(BudgetedBraces {
+        text: finish_output(&expr, config),
+        lossy,
+    })
+}
+
+/// Truncate the outermost group, keeping as many items as fit within `budget`
+///
+/// `count` gives the number of paths below an address before any eliding.
+/// Returns `None` when there is no group of several items, or not even one
+/// item fits alongside the count of the rest.
+fn truncate_outermost(
+    expr: &BraceExpr,
+    count: &dyn Fn(&[usize]) -> usize,
+    budget: usize,
+) -> Option<BraceExpr> {
+    let (address, _) = groups(expr)
+        .into_iter()
+        .find(|(address, depth)| *depth == 0 && options(expr.at(address)).len() > 1)?;
+    let items = options(expr.at(&address));
+
+    let truncate = |keep: usize| {
+        let elided: usize = (keep..items.len())
+            .map(|i| count(&[address.as_slice(), &[i]].concat()))
+            .sum();
+        let mut kept = items[..keep].to_vec();
+        kept.push(BraceExpr::Literal(format!("…+{}", elided)));
+        let mut truncated = expr.clone();
+        *truncated.at_mut(&address) = BraceExpr::Alt(kept);
+        truncated
+    };
+
+    // Binary search for the largest number of items that still fits
+    let (mut lo, mut hi) = (0, items.len() - 1);
+    let mut best = None;
+    while lo < hi {
+        let mid = (lo + hi + 1) / 2;
+        let candidate = truncate(mid);
+        if char_len(&candidate.to_string()) <= budget {
+            best = Some(candidate);
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    best
+}
+
+/// Summary of a fully collapsed group, written as a group of one item
+fn files_summary(count: usize) -> BraceExpr {
+    let label = if count == 1 {
+        "…1 file".to_string()
+    } else {
+        format!("…{} files", count)
+    };
+    BraceExpr::Alt(vec![BraceExpr::Literal(label)])
+}
+
+/// Length in characters, as seen by the reader rather than in bytes
+fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Addresses of the groups in an expression, with the number of groups around each
+///
+/// An address lists the index of the part or alternative taken at each level.
+fn groups(expr: &BraceExpr) -> Vec<(Vec<usize>, usize)> {
+    let mut out = vec![];
+    let mut stack = vec![(expr, vec![], 0)];
+    while let Some((expr, address, depth)) = stack.pop() {
+        let inner = match expr {
+            BraceExpr::Literal(_) => continue,
+            BraceExpr::Concat(parts) => (parts, depth),
+            BraceExpr::Alt(options) => {
+                out.push((address.clone(), depth));
+                (options, depth + 1)
+            }
+        };
+        for (i, part) in inner.0.iter().enumerate() {
+            let mut child = address.clone();
+            child.push(i);
+            stack.push((part, child, inner.1));
+        }
+    }
+    out
+}
+
+/// The alternatives of a group, or the expression itself otherwise
+fn options(expr: &BraceExpr) -> &[BraceExpr] {
+    match expr {
+        BraceExpr::Alt(options) => options,
+        other => std::slice::from_ref(other),
+    }
+}
@@ -0,0 +1,613 @@
+//! Incremental compression of a changing set of paths
+
+use super::ast::BraceExpr;
+use super::escape::escape_text;
+use super::expansion::compose_node;
+use super::segment::PathSegmenter;
+use super::trie::{path_segments, Node};
+use super::verify::verify_output;
+use super::{
+    brace_paths, finish_output, prepare_paths, resolve_style, BraceConfig, CaseFolding, Direction,
+};
+use crate::error::{BraceError, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A set of paths kept compressed as paths are added and removed
+///
+/// The builder owns the trie that [`brace_paths`] builds from scratch, and
+/// updates it in place: adding or removing a path only touches the nodes
+/// along that path, and rendering only recomputes the subtrees that changed
+/// since the last render. The output is always the same as [`brace_paths`]
+/// gives for [`paths`](Self::paths).
+///
+/// The shared root separators and common suffix stripped before building the
+/// trie are checked on each change, and when they change the trie is rebuilt.
+/// With a `base` directory configured, every render is a full recompute.
+///
+/// # Example
+/// ```
+/// use braces::{BraceBuilder, BraceConfig};
+///
+/// let mut builder = BraceBuilder::new(&BraceConfig::default());
+/// builder.insert("src/a.rs").unwrap();
+/// builder.insert("src/b.rs").unwrap();
+/// builder.insert("docs/c.md").unwrap();
+/// assert_eq!(builder.render().unwrap(), "{src/{a.rs,b.rs},docs/c.md}");
+///
+/// builder.remove("docs/c.md");
+/// assert_eq!(builder.render().unwrap(), "src/{a,b}.rs");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BraceBuilder {
+    config: BraceConfig,
+
+    /// Input paths as given, by the order they were inserted in
+    inputs: BTreeMap<usize, Input>,
+    /// Insertion numbers of each input path
+    by_path: HashMap<String, BTreeSet<usize>>,
+    next_input: usize,
+
+    /// Prepared paths, numbered in the order they appear in the path list
+    entries: HashMap<usize, String>,
+    next_entry: usize,
+    /// Entries of each case-folded path; when deduplicating, only the first is in the trie
+    copies: HashMap<String, BTreeSet<usize>>,
+    /// Entries in the trie
+    live: BTreeMap<usize, String>,
+    /// How many live entries share each number of leading root separators
+    roots: BTreeMap<usize, usize>,
+
+    context: Context,
+    nodes: Vec<Node>,
+    records: Vec<Record>,
+    free: Vec<usize>,
+    reprs: HashMap<usize, BraceExpr>,
+    raw_leaves: HashMap<usize, Vec<String>>,
+}
+
+/// A path as inserted, with the entries it was prepared into
+#[derive(Debug, Clone)]
+struct Input {
+    path: String,
+    entries: Vec<usize>,
+}
+
+/// What is stripped from every live path before it is added to the trie
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Context {
+    /// Leading separators shared by every path.
+    roots: usize,
+    /// Common suffix (prefix, right to left).
+    suffix: String,
+    /// The first entry, whose last segment shares a key with inner segments
+    /// when not deduplicating.
+    first: Option<usize>,
+}
+
+/// The entries passing through a trie node
+#[derive(Debug, Clone, Default)]
+struct Record {
+    key: (String, usize),
+    /// Entries by the spelling of the node's segment in them.
+    spellings: BTreeMap<String, BTreeSet<usize>>,
+    /// Entries ending at the node.
+    ends: BTreeSet<usize>,
+}
+
+impl Record {
+    /// The earliest entry through the node, which fixes its place among its siblings
+    fn first(&self) -> usize {
+        self.spellings
+            .values()
+            .filter_map(|entries| entries.first().copied())
+            .min()
+            .unwrap_or(usize::MAX)
+    }
+}
+
+impl BraceBuilder {
+    /// An empty builder compressing with `config`
+    pub fn new(config: &BraceConfig) -> Self {
+        BraceBuilder {
+            config: resolve_style(config).into_owned(),
+            inputs: BTreeMap::new(),
+            by_path: HashMap::new(),
+            next_input: 0,
+            entries: HashMap::new(),
+            next_entry: 0,
+            copies: HashMap::new(),
+            live: BTreeMap::new(),
+            roots: BTreeMap::new(),
+            context: Context::default(),
+            nodes: vec![Node::new(String::new(), 0)],
+            records: vec![Record::default()],
+            free: vec![],
+            reprs: HashMap::new(),
+            raw_leaves: HashMap::new(),
+        }
+    }
+
+    /// Add a path after those already present
+    ///
+    /// The path is validated and normalised as [`brace_paths`] would, and is
+    /// not added if that fails.
+    pub fn insert(&mut self, path: impl AsRef<str>) -> Result<()> {
+        let path = path.as_ref();
+        let prepared = prepare_paths(&[path], &self.config)?;
+
+        let mut entries = Vec::with_capacity(prepared.len());
+        for prepared_path in prepared {
+            let entry = self.next_entry;
+            self.next_entry += 1;
+            entries.push(entry);
+
+            let copies = self
+                .copies
+                .entry(self.config.case_folding.fold(&prepared_path))
+                .or_default();
+            copies.insert(entry);
+            let shown = !self.config.deduplicate_inputs || copies.len() == 1;
+            self.entries.insert(entry, prepared_path.clone());
+            if shown {
+                self.show(entry, prepared_path);
+            }
+        }
+
+        let input = self.next_input;
+        self.next_input += 1;
+        self.by_path
+            .entry(path.to_string())
+            .or_default()
+            .insert(input);
+        self.inputs.insert(
+            input,
+            Input {
+                path: path.to_string(),
+                entries,
+            },
+        );
+        Ok(())
+    }
+
+    /// Remove the earliest occurrence of a path, returning whether it was present
+    pub fn remove(&mut self, path: &str) -> bool {
+        let Some(inserted) = self.by_path.get_mut(path) else {
+            return false;
+        };
+        let input = inserted
+            .pop_first()
+            .expect("paths are dropped with their last insertion");
+        if inserted.is_empty() {
+            self.by_path.remove(path);
+        }
+
+        let Some(Input { entries, .. }) = self.inputs.remove(&input) else {
+            return false;
+        };
+        for entry in entries {
+            let Some(prepared_path) = self.entries.remove(&entry) else {
+                continue;
+            };
+            let folded = self.config.case_folding.fold(&prepared_path);
+            let Some(copies) = self.copies.get_mut(&folded) else {
+                continue;
+            };
+            let dedup = self.config.deduplicate_inputs;
+            let was_shown = !dedup || copies.first() == Some(&entry);
+            copies.remove(&entry);
+            // A later copy of a deduplicated path takes its place
+            let promoted = if dedup && was_shown {
+                copies.first().copied()
+            } else {
+                None
+            };
+            if copies.is_empty() {
+                self.copies.remove(&folded);
+            }
+
+            if was_shown {
+                self.hide(entry);
+            }
+            if let Some(next) = promoted {
+                let next_path = self.entries[&next].clone();
+                self.show(next, next_path);
+            }
+        }
+        true
+    }
+
+    /// The paths present, in the order they were inserted
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.inputs.values().map(|input| input.path.as_str())
+    }
+
+    /// The number of paths present
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Whether no paths are present
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Compress the paths present, as [`brace_paths`] does
+    ///
+    /// Only subtrees changed since the last render are recomputed.
+    pub fn render(&mut self) -> Result<String> {
+        if self.inputs.is_empty() {
+            return Err(BraceError::EmptyInput);
+        }
+        if self.config.base.is_some() {
+            let paths: Vec<&str> = self.paths().collect();
+            return brace_paths(&paths, &self.config);
+        }
+
+        self.update_reprs();
+        let config = &self.config;
+        let result = self.reprs.get(&0).cloned().unwrap_or_else(BraceExpr::empty);
+        let common = BraceExpr::Literal(escape_text(&self.context.suffix, config.escape));
+        let mut expr = match config.direction {
+            Direction::LeftToRight => BraceExpr::concat([result, common]),
+            Direction::RightToLeft => BraceExpr::concat([common, result]),
+        };
+        for _ in 0..self.context.roots {
+            let root = BraceExpr::Literal(escape_text(&config.path_separator, config.escape));
+            expr = BraceExpr::concat([root, expr]);
+        }
+
+        let paths: Vec<String> = self.live.values().cloned().collect();
+        let expr = verify_output(expr, &paths, config)?;
+        Ok(finish_output(&expr, config))
+    }
+
+    /// Add an entry to the trie
+    fn show(&mut self, entry: usize, path: String) {
+        *self.roots.entry(self.root_depth(&path)).or_default() += 1;
+        self.live.insert(entry, path);
+        if self.config.base.is_some() {
+            return;
+        }
+
+        let context = self.next_context(Some(entry));
+        match context {
+            Some(context) => {
+                self.context = context;
+                self.rebuild();
+            }
+            None => self.add(entry),
+        }
+    }
+
+    /// Remove an entry from the trie
+    fn hide(&mut self, entry: usize) {
+        let Some(path) = self.live.remove(&entry) else {
+            return;
+        };
+        let depth = self.root_depth(&path);
+        if let Some(count) = self.roots.get_mut(&depth) {
+            *count -= 1;
+            if *count == 0 {
+                self.roots.remove(&depth);
+            }
+        }
+        if self.config.base.is_some() {
+            return;
+        }
+
+        let context = self.next_context(None);
+        match context {
+            Some(context) => {
+                self.context = context;
+                self.rebuild();
+            }
+            None => self.drop_entry(entry, &path),
+        }
+    }
+
+    /// The context for the live entries, if it differs from the current one
+    ///
+    /// Adding an entry can only cut the common suffix down to that entry's,
+    /// and removing one can only lengthen it, unless the root changes too.
+    fn next_context(&self, added: Option<usize>) -> Option<Context> {
+        let roots = self.root_count();
+        let first = if self.config.deduplicate_inputs {
+            None
+        } else {
+            self.live.keys().next().copied()
+        };
+
+        let same_root = roots == self.context.roots;
+        let suffix = if self.config.stable_output {
+            String::new()
+        } else {
+            match added {
+                Some(entry) if same_root && self.live.len() > 1 => {
+                    let path = self.rooted(&self.live[&entry], roots);
+                    shared_end(&self.context.suffix, path, self.config.direction).to_string()
+                }
+                _ if same_root && added.is_none() => {
+                    self.common_suffix(roots, self.context.suffix.len())
+                }
+                _ => self.common_suffix(roots, 0),
+            }
+        };
+
+        let context = Context {
+            roots,
+            suffix,
+            first,
+        };
+        (context != self.context).then_some(context)
+    }
+
+    /// Leading separators stripped from every path: the fewest any path has, if there are several
+    fn root_count(&self) -> usize {
+        if self.live.len() > 1 {
+            self.roots.keys().next().copied().unwrap_or(0)
+        } else {
+            0
+        }
+    }
+
+    /// Leading separators that could be stripped from a path, leaving more than a separator
+    fn root_depth(&self, path: &str) -> usize {
+        let sep = self.config.path_separator.as_str();
+        if sep.is_empty() {
+            return 0;
+        }
+        let mut rest = path;
+        let mut depth = 0;
+        while rest.starts_with(sep) && rest != sep {
+            rest = &rest[sep.len()..];
+            depth += 1;
+        }
+        depth
+    }
+
+    /// The path without `roots` leading separators
+    fn rooted<'p>(&self, path: &'p str, roots: usize) -> &'p str {
+        &path[roots * self.config.path_separator.len()..]
+    }
+
+    /// The common suffix of the live paths, stopping once it is `floor` bytes long
+    fn common_suffix(&self, roots: usize, floor: usize) -> String {
+        let mut paths = self.live.values().map(|p| self.rooted(p, roots));
+        let Some(mut common) = paths.next() else {
+            return String::new();
+        };
+        for path in paths {
+            if common.len() <= floor {
+                break;
+            }
+            common = shared_end(common, path, self.config.direction);
+        }
+        common.to_string()
+    }
+
+    /// The segments of a live path as they are added to the trie
+    fn segments(&self, path: &str) -> Vec<String> {
+        let rest = self.rooted(path, self.context.roots);
+        let suffix = self.context.suffix.as_str();
+        let stripped = match self.config.direction {
+            Direction::LeftToRight => rest.strip_suffix(suffix).unwrap_or(rest),
+            Direction::RightToLeft => rest.strip_prefix(suffix).unwrap_or(rest),
+        };
+        let segmenter = PathSegmenter::new(&self.config.path_separator, &self.config);
+        path_segments(stripped, &segmenter, &self.config)
+    }
+
+    /// The key of an entry's segment among its siblings
+    fn key(&self, entry: usize, segment: &str, is_last: bool) -> (String, usize) {
+        let folded = self.config.case_folding.fold(segment);
+        if !self.config.deduplicate_inputs && is_last {
+            // Ids follow the path list, and the first path's id is the one inner segments use
+            let id = if self.context.first == Some(entry) {
+                0
+            } else {
+                entry + 1
+            };
+            (folded, id)
+        } else {
+            (folded, 0)
+        }
+    }
+
+    /// Rebuild the trie from the live entries
+    fn rebuild(&mut self) {
+        self.nodes = vec![Node::new(String::new(), 0)];
+        self.records = vec![Record::default()];
+        self.free.clear();
+        self.reprs.clear();
+        self.raw_leaves.clear();
+        let entries: Vec<usize> = self.live.keys().copied().collect();
+        for entry in entries {
+            self.add(entry);
+        }
+    }
+
+    /// Add a live entry's path to the trie
+    fn add(&mut self, entry: usize) {
+        let segments = self.segments(&self.live[&entry]);
+        let mut trail = vec![0];
+        for (i, segment) in segments.iter().enumerate() {
+            let is_last = i + 1 == segments.len();
+            let key = self.key(entry, segment, is_last);
+            let parent = trail[trail.len() - 1];
+            let child = match self.nodes[parent].children.get(&key) {
+                Some(&child) => child,
+                None => {
+                    let child = self.alloc(key.clone(), self.nodes[parent].depth + 1);
+                    self.nodes[parent].children.insert(key, child);
+                    child
+                }
+            };
+            let record = &mut self.records[child];
+            record
+                .spellings
+                .entry(segment.clone())
+                .or_default()
+                .insert(entry);
+            if is_last {
+                record.ends.insert(entry);
+            }
+            trail.push(child);
+        }
+        self.refresh(&trail);
+    }
+
+    /// Remove an entry's path from the trie
+    fn drop_entry(&mut self, entry: usize, path: &str) {
+        let segments = self.segments(path);
+        let mut trail = vec![0];
+        for (i, segment) in segments.iter().enumerate() {
+            let is_last = i + 1 == segments.len();
+            let key = self.key(entry, segment, is_last);
+            let parent = trail[trail.len() - 1];
+            let Some(&child) = self.nodes[parent].children.get(&key) else {
+                break;
+            };
+            let record = &mut self.records[child];
+            if let Some(entries) = record.spellings.get_mut(segment) {
+                entries.remove(&entry);
+                if entries.is_empty() {
+                    record.spellings.remove(segment);
+                }
+            }
+            if is_last {
+                record.ends.remove(&entry);
+            }
+            trail.push(child);
+        }
+        self.refresh(&trail);
+    }
+
+    /// A new node, reusing a removed one if there is one
+    fn alloc(&mut self, key: (String, usize), depth: usize) -> usize {
+        let record = Record {
+            key,
+            ..Record::default()
+        };
+        match self.free.pop() {
+            Some(idx) => {
+                self.nodes[idx] = Node::new(String::new(), depth);
+                self.records[idx] = record;
+                idx
+            }
+            None => {
+                self.nodes.push(Node::new(String::new(), depth));
+                self.records.push(record);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Bring the nodes along a changed path up to date, deepest first
+    ///
+    /// Nodes no entry passes through any more are removed, the others are
+    /// relabelled and re-placed among their siblings, and all lose their
+    /// cached representation.
+    fn refresh(&mut self, trail: &[usize]) {
+        for pair in trail.windows(2).rev() {
+            let (parent, idx) = (pair[0], pair[1]);
+            self.reprs.remove(&idx);
+            self.raw_leaves.remove(&idx);
+
+            let record = &self.records[idx];
+            if record.spellings.is_empty() {
+                let key = record.key.clone();
+                self.nodes[parent].children.remove(&key);
+                self.free.push(idx);
+                continue;
+            }
+            let label = self.label(record);
+            let node = &mut self.nodes[idx];
+            node.label = label;
+            node.is_leaf = !record.ends.is_empty();
+            node.is_trailing_sep = node.is_leaf && record.key.0.is_empty() && node.depth > 1;
+
+            let records = &self.records;
+            self.nodes[parent]
+                .children
+                .sort_by_key(|&child| records[child].first());
+        }
+        self.reprs.remove(&0);
+        self.raw_leaves.remove(&0);
+    }
+
+    /// The label of a node, spelled as the configured case folding picks
+    fn label(&self, record: &Record) -> String {
+        let first = |entries: &BTreeSet<usize>| entries.first().copied().unwrap_or(usize::MAX);
+        let spelling = match self.config.case_folding {
+            // The most used spelling, the earliest on ties
+            CaseFolding::MostCommon => record.spellings.iter().max_by(|a, b| {
+                a.1.len()
+                    .cmp(&b.1.len())
+                    .then_with(|| first(b.1).cmp(&first(a.1)))
+            }),
+            _ => record
+                .spellings
+                .iter()
+                .min_by_key(|(_, entries)| first(entries)),
+        };
+        let spelling = spelling.map_or("", |(spelling, _)| spelling.as_str());
+        escape_text(
+            &self.config.case_folding.spell(spelling),
+            self.config.escape,
+        )
+    }
+
+    /// Compute the representations of nodes changed since the last render
+    fn update_reprs(&mut self) {
+        let sep = self.config.path_separator.as_str();
+        let mut stack = vec![(0, false)];
+        while let Some((idx, composed)) = stack.pop() {
+            if composed {
+                let (repr, raws) = compose_node(
+                    &self.nodes,
+                    idx,
+                    0,
+                    sep,
+                    &self.config,
+                    &self.reprs,
+                    &self.raw_leaves,
+                );
+                self.reprs.insert(idx, repr);
+                self.raw_leaves.insert(idx, raws);
+            } else if !self.reprs.contains_key(&idx) {
+                stack.push((idx, true));
+                stack.extend(
+                    self.nodes[idx]
+                        .children
+                        .values()
+                        .map(|&child| (child, false)),
+                );
+            }
+        }
+    }
+}
+
+/// The longest suffix of `common` that `path` also ends with (prefix, right to left)
+fn shared_end<'c>(common: &'c str, path: &str, direction: Direction) -> &'c str {
+    match direction {
+        Direction::LeftToRight => {
+            let shared: usize = common
+                .chars()
+                .rev()
+                .zip(path.chars().rev())
+                .take_while(|(a, b)| a == b)
+                .map(|(c, _)| c.len_utf8())
+                .sum();
+            &common[common.len() - shared..]
+        }
+        Direction::RightToLeft => {
+            let shared: usize = common
+                .chars()
+                .zip(path.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(c, _)| c.len_utf8())
+                .sum();
+            &common[..shared]
+        }
+    }
+}
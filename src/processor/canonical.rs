@@ -0,0 +1,360 @@
+//! Canonical forms and equivalence of brace expressions, as sets of paths
+
+use super::parse::{parse_braces, SpannedExpr, SpannedKind};
+use super::range::BraceRange;
+use super::verify::VerifyMode;
+use super::{brace_paths, BraceConfig, CaseFolding};
+use crate::error::{BraceError, Result};
+use std::collections::{BTreeSet, HashMap};
+
+/// Rewrite a brace expression in a deterministic normal form
+///
+/// Two expressions denoting the same set of paths have the same canonical
+/// form, however they are written: order and duplicates are ignored. The
+/// expression is read with `config.escape`, and the canonical form is the
+/// sorted path set compressed with the default options, keeping only the
+/// separator, path style and escape policy from `config`.
+///
+/// The expression is expanded in full, so the work grows with the number of
+/// paths it denotes. Use [`equivalent`] to compare expressions too large to
+/// expand.
+///
+/// # Example
+/// ```
+/// use braces::{canonicalize, BraceConfig};
+///
+/// let config = BraceConfig::default();
+/// assert_eq!(canonicalize("{a/c,a/b,a/b}", &config).unwrap(), "a/{b,c}");
+/// assert_eq!(canonicalize("a/{c,b}", &config).unwrap(), "a/{b,c}");
+/// ```
+pub fn canonicalize(expr: &str, config: &BraceConfig) -> Result<String> {
+    let paths: BTreeSet<String> = parse(expr, config)?.expand().into_iter().collect();
+    let paths: Vec<String> = paths.into_iter().collect();
+
+    let canonical = BraceConfig {
+        path_separator: config.path_separator.clone(),
+        path_style: config.path_style,
+        separators: vec![],
+        escape: config.escape,
+        sort_items: true,
+        verify: VerifyMode::Fallback,
+        ..Default::default()
+    };
+    brace_paths(&paths, &canonical)
+}
+
+/// Whether two brace expressions denote the same set of paths
+///
+/// Both are read with `config.escape`. The trees are compared first, with
+/// groups taken as unordered sets, so that expressions differing only in the
+/// order or nesting of alternatives are matched at once. Otherwise each is
+/// compiled to an automaton over characters and the two are compared state by
+/// state (Hopcroft and Karp's algorithm), so neither side is expanded: the
+/// work grows with the number of distinct prefixes and suffixes, as in
+/// `a/{b,c}` against `{a/c,a/b}`, rather than with the number of paths, which
+/// is exponential in the number of groups for `{a,b}{c,d}{e,f}…`. Sequences
+/// become character classes, so `{1..1000000000}` is not expanded either,
+/// except for sequences with a step, whose items are listed. With
+/// `config.case_folding`, paths differing only in case are the same path.
+///
+/// # Example
+/// ```
+/// use braces::{equivalent, BraceConfig};
+///
+/// let config = BraceConfig::default();
+/// assert!(equivalent("a/{b,c}", "{a/c,a/b}", &config).unwrap());
+/// assert!(!equivalent("a/{b,c}", "a/b", &config).unwrap());
+/// ```
+pub fn equivalent(a: &str, b: &str, config: &BraceConfig) -> Result<bool> {
+    let a = Shape::of(&parse(a, config)?, config.case_folding);
+    let b = Shape::of(&parse(b, config)?, config.case_folding);
+    if a == b {
+        return Ok(true);
+    }
+    Ok(same_language(&Nfa::of(&a), &Nfa::of(&b)))
+}
+
+/// Parse an expression, failing on any syntax error
+pub(crate) fn parse(expr: &str, config: &BraceConfig) -> Result<SpannedExpr> {
+    let parsed = parse_braces(expr, config.escape);
+    if !parsed.errors.is_empty() {
+        return Err(BraceError::InvalidBraceSyntax {
+            errors: parsed.errors,
+        });
+    }
+    Ok(parsed.expr)
+}
+
+/// Structure of an expression up to the laws of set union
+///
+/// Literal text is unescaped and folded as configured, sequences `{1..3}`
+/// become groups of character classes, concatenations are flattened with
+/// adjacent text merged, and groups are flattened, sorted and deduplicated, so
+/// equal shapes always denote equal path sets.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Shape {
+    Text(String),
+    /// One character between two, inclusive
+    Class(char, char),
+    Seq(Vec<Shape>),
+    Union(Vec<Shape>),
+}
+
+impl Shape {
+    fn of(expr: &SpannedExpr, case_folding: CaseFolding) -> Shape {
+        let of = |expr| Shape::of(expr, case_folding);
+        match &expr.kind {
+            SpannedKind::Literal(text) => Shape::Text(case_folding.fold(text)),
+            SpannedKind::Trivia => Shape::Text(String::new()),
+            SpannedKind::Concat(parts) => Shape::seq(parts.iter().map(of)),
+            SpannedKind::Alt(options) => Shape::union(options.iter().map(of)),
+            SpannedKind::Range(range) => Shape::range(range, case_folding),
+        }
+    }
+
+    /// The items of a sequence, as classes where it has no step
+    fn range(range: &BraceRange, case_folding: CaseFolding) -> Shape {
+        let Some(patterns) = range.classes() else {
+            return Shape::union(
+                range
+                    .items()
+                    .map(|item| Shape::Text(case_folding.fold(&item))),
+            );
+        };
+        Shape::union(patterns.into_iter().map(|pattern| {
+            Shape::seq(pattern.into_iter().map(|(low, high)| match case_folding {
+                CaseFolding::Off => Shape::class(low, high),
+                _ => Shape::folded_class(low, high),
+            }))
+        }))
+    }
+
+    fn class(low: char, high: char) -> Shape {
+        if low == high {
+            Shape::Text(low.to_string())
+        } else {
+            Shape::Class(low, high)
+        }
+    }
+
+    /// A class with its characters folded to lowercase
+    ///
+    /// ASCII capitals are moved onto the lowercase letters; any other cased
+    /// character is listed, as its lowercase need not be a single character.
+    fn folded_class(low: char, high: char) -> Shape {
+        if !high.is_ascii() {
+            return Shape::union((low..=high).map(|c| Shape::Text(c.to_lowercase().collect())));
+        }
+        let (low, high) = (low as u8, high as u8);
+        let mut parts = vec![];
+        let mut keep = |low: u8, high: u8| {
+            if low <= high {
+                parts.push(Shape::class(low as char, high as char));
+            }
+        };
+        keep(low, high.min(b'A' - 1));
+        keep(
+            low.max(b'A').to_ascii_lowercase(),
+            high.min(b'Z').to_ascii_lowercase(),
+        );
+        keep(low.max(b'Z' + 1), high);
+        Shape::union(parts.into_iter())
+    }
+
+    fn seq(parts: impl Iterator<Item = Shape>) -> Shape {
+        let mut out: Vec<Shape> = vec![];
+        for part in parts {
+            let flattened = match part {
+                Shape::Seq(inner) => inner,
+                other => vec![other],
+            };
+            for part in flattened {
+                match (out.last_mut(), part) {
+                    (_, Shape::Text(text)) if text.is_empty() => {}
+                    (Some(Shape::Text(prev)), Shape::Text(text)) => prev.push_str(&text),
+                    (_, part) => out.push(part),
+                }
+            }
+        }
+        match out.len() {
+            0 => Shape::Text(String::new()),
+            1 => out.remove(0),
+            _ => Shape::Seq(out),
+        }
+    }
+
+    fn union(options: impl Iterator<Item = Shape>) -> Shape {
+        let mut out: Vec<Shape> = options
+            .flat_map(|option| match option {
+                Shape::Union(inner) => inner,
+                other => vec![other],
+            })
+            .collect();
+        out.sort();
+        out.dedup();
+        if out.len() == 1 {
+            out.remove(0)
+        } else {
+            Shape::Union(out)
+        }
+    }
+}
+
+/// A nondeterministic automaton accepting the paths of a [`Shape`]
+///
+/// State 0 is the start, and `accept` the single accepting state. Each
+/// transition reads one character from an inclusive range of code points.
+struct Nfa {
+    steps: Vec<Vec<(u32, u32, usize)>>,
+    /// Transitions taken without reading a character
+    skips: Vec<Vec<usize>>,
+    accept: usize,
+}
+
+impl Nfa {
+    fn of(shape: &Shape) -> Nfa {
+        let mut nfa = Nfa {
+            steps: vec![vec![]],
+            skips: vec![vec![]],
+            accept: 0,
+        };
+        nfa.accept = nfa.build(shape, 0);
+        nfa
+    }
+
+    fn state(&mut self) -> usize {
+        self.steps.push(vec![]);
+        self.skips.push(vec![]);
+        self.steps.len() - 1
+    }
+
+    /// Add the states for `shape` after state `from`, returning the state it ends in
+    fn build(&mut self, shape: &Shape, from: usize) -> usize {
+        match shape {
+            Shape::Text(text) => text.chars().fold(from, |at, c| {
+                let next = self.state();
+                self.steps[at].push((c as u32, c as u32, next));
+                next
+            }),
+            Shape::Class(low, high) => {
+                let next = self.state();
+                self.steps[from].push((*low as u32, *high as u32, next));
+                next
+            }
+            Shape::Seq(parts) => parts.iter().fold(from, |at, part| self.build(part, at)),
+            Shape::Union(options) => {
+                let end = self.state();
+                for option in options {
+                    let start = self.state();
+                    self.skips[from].push(start);
+                    let last = self.build(option, start);
+                    self.skips[last].push(end);
+                }
+                end
+            }
+        }
+    }
+
+    /// The states reachable from `states` without reading, sorted
+    fn closure(&self, states: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        let mut seen = BTreeSet::new();
+        let mut stack: Vec<usize> = states.into_iter().collect();
+        while let Some(state) = stack.pop() {
+            if seen.insert(state) {
+                stack.extend(&self.skips[state]);
+            }
+        }
+        seen.into_iter().collect()
+    }
+
+    /// The set of states reached from `states` by reading `c`
+    fn step(&self, states: &[usize], c: u32) -> Vec<usize> {
+        self.closure(
+            states
+                .iter()
+                .flat_map(|&s| &self.steps[s])
+                .filter(|&&(low, high, _)| low <= c && c <= high)
+                .map(|&(_, _, to)| to),
+        )
+    }
+
+    /// The ranges that can be read from some state of `states`
+    fn readable(&self, states: &[usize]) -> Vec<(u32, u32)> {
+        states
+            .iter()
+            .flat_map(|&s| self.steps[s].iter().map(|&(low, high, _)| (low, high)))
+            .collect()
+    }
+}
+
+/// One character from each run of characters that every range treats alike
+///
+/// The runs split the ranges at each of their ends, so reading any character
+/// of a run reaches the same states as reading the one returned.
+fn representatives(ranges: &[(u32, u32)]) -> Vec<u32> {
+    let bounds: BTreeSet<u32> = ranges
+        .iter()
+        .flat_map(|&(low, high)| [low, high + 1])
+        .collect();
+    bounds
+        .into_iter()
+        .filter(|&c| ranges.iter().any(|&(low, high)| low <= c && c <= high))
+        .collect()
+}
+
+/// Whether two automata accept the same strings, by Hopcroft and Karp's algorithm
+///
+/// The automata are determinised as they are explored, and pairs of state
+/// sets reached by the same input are merged in a union-find; the languages
+/// differ exactly when some merged pair disagrees on acceptance.
+fn same_language(a: &Nfa, b: &Nfa) -> bool {
+    let mut classes = Classes::default();
+    let start = (a.closure([0]), b.closure([0]));
+    classes.merge((false, start.0.clone()), (true, start.1.clone()));
+    let mut pending = vec![start];
+    while let Some((x, y)) = pending.pop() {
+        if x.contains(&a.accept) != y.contains(&b.accept) {
+            return false;
+        }
+        let ranges = [a.readable(&x), b.readable(&y)].concat();
+        for c in representatives(&ranges) {
+            let (x, y) = (a.step(&x, c), b.step(&y, c));
+            if classes.merge((false, x.clone()), (true, y.clone())) {
+                pending.push((x, y));
+            }
+        }
+    }
+    true
+}
+
+/// Determinised states of two automata, by side and state set, in a union-find
+#[derive(Default)]
+struct Classes {
+    ids: HashMap<(bool, Vec<usize>), usize>,
+    parent: Vec<usize>,
+}
+
+impl Classes {
+    /// The representative of the class of `state`
+    fn find(&mut self, state: (bool, Vec<usize>)) -> usize {
+        let parent = &mut self.parent;
+        let mut x = *self.ids.entry(state).or_insert_with(|| {
+            parent.push(parent.len());
+            parent.len() - 1
+        });
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Put two states in one class, returning whether they were apart
+    fn merge(&mut self, x: (bool, Vec<usize>), y: (bool, Vec<usize>)) -> bool {
+        let (x, y) = (self.find(x), self.find(y));
+        if x != y {
+            self.parent[x] = y;
+        }
+        x != y
+    }
+}
@@ -0,0 +1,40 @@
+//! Case-insensitive grouping for case-insensitive filesystems
+
+/// Whether path segments are grouped case-insensitively, and how they are spelled
+///
+/// When folding, `Src/Lib.rs` and `src/lib.rs` are the same path: they are
+/// deduplicated and share trie nodes, and each segment is printed with a
+/// single spelling chosen by the variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseFolding {
+    /// Compare segments case-sensitively (default).
+    #[default]
+    Off,
+
+    /// Fold case, printing the first spelling seen for each segment.
+    FirstSeen,
+
+    /// Fold case, printing the spelling used by the most paths (first seen on ties).
+    MostCommon,
+
+    /// Fold case, printing every segment in lowercase.
+    Lowercase,
+}
+
+impl CaseFolding {
+    /// The key under which text is compared
+    pub(crate) fn fold(self, text: &str) -> String {
+        match self {
+            CaseFolding::Off => text.to_string(),
+            _ => text.to_lowercase(),
+        }
+    }
+
+    /// The spelling to print for text first seen as `text`
+    pub(crate) fn spell(self, text: &str) -> String {
+        match self {
+            CaseFolding::Lowercase => text.to_lowercase(),
+            _ => text.to_string(),
+        }
+    }
+}
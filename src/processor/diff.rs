@@ -0,0 +1,203 @@
+//! Differences between two path sets in brace form
+
+use super::algebra::{memberships, prepare_set};
+use super::direction::Direction;
+use super::normalise::find_separators;
+use super::style::PathStyle;
+use super::{finish_output, render_verified, resolve_style, BraceConfig};
+use crate::error::{BraceError, Result};
+use std::fmt;
+
+/// Stands in for `+` while compressing, so that it cannot match a path's own text
+const ADDED: char = '\u{E000}';
+
+/// Stands in for `-` while compressing
+const REMOVED: char = '\u{E001}';
+
+/// The difference between two path sets, in brace form
+///
+/// Each part is compressed with the config given to [`diff_paths`], and is
+/// `None` when it holds no paths. Its [`Display`](fmt::Display) impl writes
+/// the additions and removals as `+ tests/{a,b}.rs / - docs/x.md`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BraceDiff {
+    /// Paths only in the after set.
+    pub added: Option<String>,
+
+    /// Paths only in the before set.
+    pub removed: Option<String>,
+
+    /// Paths in both sets.
+    pub unchanged: Option<String>,
+
+    /// Added and removed paths in one expression, with the last segment of each
+    /// marked `+` or `-`: `src/{+new.rs,-old.rs}`.
+    ///
+    /// With `highlight` enabled, the marked segments are coloured green and red.
+    pub annotated: Option<String>,
+}
+
+impl BraceDiff {
+    /// Whether the two sets hold the same paths
+    pub fn is_empty(&self) -> bool {
+        self.added.is_none() && self.removed.is_none()
+    }
+}
+
+impl fmt::Display for BraceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.added, &self.removed) {
+            (Some(added), Some(removed)) => write!(f, "+ {} / - {}", added, removed),
+            (Some(added), None) => write!(f, "+ {}", added),
+            (None, Some(removed)) => write!(f, "- {}", removed),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Compare two path sets, compressing what was added, removed and kept
+///
+/// Both sets are prepared as by [`brace_paths`](crate::brace_paths) and merged
+/// into one trie, whose leaves are marked as added, removed or unchanged by
+/// the set they came from. Paths are listed in the order they first appear in
+/// `before` followed by `after`.
+///
+/// The annotated form marks paths with the private-use characters U+E000 and
+/// U+E001 while compressing, so paths containing either are rejected.
+///
+/// # Example
+/// ```
+/// use braces::{diff_paths, BraceConfig};
+///
+/// let before = vec!["src/lib.rs", "src/old.rs", "docs/x.md"];
+/// let after = vec!["src/lib.rs", "src/new.rs", "tests/a.rs", "tests/b.rs"];
+/// let diff = diff_paths(&before, &after, &BraceConfig::default()).unwrap();
+/// assert_eq!(diff.added.as_deref(), Some("{src/new,tests/{a,b}}.rs"));
+/// assert_eq!(diff.removed.as_deref(), Some("{src/old.rs,docs/x.md}"));
+/// assert_eq!(diff.unchanged.as_deref(), Some("src/lib.rs"));
+/// assert_eq!(
+///     diff.annotated.as_deref(),
+///     Some("{src/{-old.rs,+new.rs},docs/-x.md,tests/{+a.rs,+b.rs}}")
+/// );
+/// assert_eq!(diff.to_string(), "+ {src/new,tests/{a,b}}.rs / - {src/old.rs,docs/x.md}");
+/// ```
+pub fn diff_paths(
+    before: &[impl AsRef<str>],
+    after: &[impl AsRef<str>],
+    config: &BraceConfig,
+) -> Result<BraceDiff> {
+    let config = &resolve_style(config);
+    let before = prepare_set(before, config)?;
+    let after = prepare_set(after, config)?;
+    let combined: Vec<String> = before.iter().chain(&after).cloned().collect();
+    if let Some(path) = combined.iter().find(|p| p.contains([ADDED, REMOVED])) {
+        return Err(BraceError::InvalidBraceInput {
+            path: path.clone(),
+            reason: "contains a character reserved for diff markers (U+E000 or U+E001)".to_string(),
+        });
+    }
+
+    let mut added = vec![];
+    let mut removed = vec![];
+    let mut unchanged = vec![];
+    let mut annotated = vec![];
+    for (i, in_before, in_after) in memberships(&combined, before.len(), config) {
+        let path = combined[i].clone();
+        match (in_before, in_after) {
+            (true, true) => unchanged.push(path),
+            (true, false) => {
+                annotated.push(mark(&path, REMOVED, config));
+                removed.push(path);
+            }
+            _ => {
+                annotated.push(mark(&path, ADDED, config));
+                added.push(path);
+            }
+        }
+    }
+
+    let compress = |paths: &[String]| -> Result<Option<String>> {
+        if paths.is_empty() {
+            return Ok(None);
+        }
+        let expr = render_verified(paths, config)?;
+        Ok(Some(finish_output(&expr, config)))
+    };
+    let annotated = if annotated.is_empty() {
+        None
+    } else {
+        let expr = render_verified(&annotated, config)?;
+        Some(finish_output(
+            &expr.map_literals(&|text| show_marks(text, config)),
+            config,
+        ))
+    };
+
+    Ok(BraceDiff {
+        added: compress(&added)?,
+        removed: compress(&removed)?,
+        unchanged: compress(&unchanged)?,
+        annotated,
+    })
+}
+
+/// Insert `marker` at the start of the segment a path ends with in the trie
+///
+/// Right to left, or when paths are not split at a separator, the whole path
+/// is marked.
+fn mark(path: &str, marker: char, config: &BraceConfig) -> String {
+    let sep = config.path_separator.as_str();
+    let whole = config.direction == Direction::RightToLeft
+        || config.path_style == PathStyle::Url
+        || sep.is_empty();
+    let at = if whole {
+        0
+    } else {
+        // A trailing separator is not the start of a segment
+        find_separators(path, sep, &config.separators)
+            .into_iter()
+            .rev()
+            .map(|(at, found)| at + found.len())
+            .find(|&end| end < path.len())
+            .unwrap_or(0)
+    };
+    format!("{}{}{}", &path[..at], marker, &path[at..])
+}
+
+/// Replace the markers in an output literal with `+` and `-`, coloured if highlighting
+fn show_marks(text: &str, config: &BraceConfig) -> String {
+    #[cfg(all(feature = "cli", feature = "highlight"))]
+    if config.highlight {
+        return colour_marks(text, &config.path_separator);
+    }
+    #[cfg(not(all(feature = "cli", feature = "highlight")))]
+    let _ = config;
+
+    text.replace(ADDED, "+").replace(REMOVED, "-")
+}
+
+/// Colour each marker and the text following it, up to the next separator or the end of the literal
+#[cfg(all(feature = "cli", feature = "highlight"))]
+fn colour_marks(text: &str, sep: &str) -> String {
+    use crate::highlight::highlight_change;
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find([ADDED, REMOVED]) {
+        out.push_str(&rest[..start]);
+        let marker = rest[start..].chars().next().unwrap_or(ADDED);
+        let after = &rest[start + marker.len_utf8()..];
+        let end = after
+            .char_indices()
+            .find(|&(i, c)| "{},".contains(c) || (!sep.is_empty() && after[i..].starts_with(sep)))
+            .map_or(after.len(), |(i, _)| i);
+        let shown = if marker == ADDED { '+' } else { '-' };
+        out.push_str(&highlight_change(
+            &format!("{}{}", shown, &after[..end]),
+            marker == ADDED,
+        ));
+        rest = &after[end..];
+    }
+    out.push_str(rest);
+    out
+}
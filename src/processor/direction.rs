@@ -0,0 +1,64 @@
+//! Grouping segments from the left or from the right
+
+use super::ast::BraceExpr;
+
+/// Which end of a path segments are grouped from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// Group from the first segment, as for file paths and reverse-DNS names (default).
+    #[default]
+    LeftToRight,
+
+    /// Group from the last segment, as for host names: `{api,web}.prod.example.com`.
+    RightToLeft,
+}
+
+impl Direction {
+    /// Put segments into grouping order (their reading order reversed, right to left)
+    pub(crate) fn order<T>(self, segments: &mut [T]) {
+        if self == Direction::RightToLeft {
+            segments.reverse();
+        }
+    }
+
+    /// Join a node label to the text below it in the trie, in reading order
+    pub(crate) fn join(self, label: &str, sep: &str, rest: &str) -> String {
+        match self {
+            Direction::LeftToRight => format!("{}{}{}", label, sep, rest),
+            Direction::RightToLeft => format!("{}{}{}", rest, sep, label),
+        }
+    }
+
+    /// Join a node label to the expression below it in the trie, in reading order
+    pub(crate) fn join_expr(self, label: &str, sep: &str, rest: BraceExpr) -> BraceExpr {
+        match self {
+            Direction::LeftToRight => {
+                BraceExpr::concat([BraceExpr::Literal(format!("{}{}", label, sep)), rest])
+            }
+            Direction::RightToLeft => {
+                BraceExpr::concat([rest, BraceExpr::Literal(format!("{}{}", sep, label))])
+            }
+        }
+    }
+
+    /// Remove a node label joined by [`Direction::join`], returning the rest
+    pub(crate) fn strip_label<'a>(self, text: &'a str, label: &str, sep: &str) -> Option<&'a str> {
+        match self {
+            Direction::LeftToRight => text.strip_prefix(label)?.strip_prefix(sep),
+            Direction::RightToLeft => text.strip_suffix(label)?.strip_suffix(sep),
+        }
+    }
+
+    /// Sort items comparing segments from the grouping end
+    pub(crate) fn sort<T: ToString>(self, items: &mut [T], sep: &str) {
+        match self {
+            Direction::RightToLeft if !sep.is_empty() => items.sort_by_cached_key(|item| {
+                item.to_string()
+                    .rsplit(sep)
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            }),
+            _ => items.sort_by_cached_key(ToString::to_string),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Escaping of brace metacharacters that occur literally in paths
+
+use super::parse::parse_braces;
+
+/// How literal brace metacharacters in paths are written in the output
+///
+/// The characters `{`, `}` and `,` would otherwise be read as brace syntax,
+/// and `\` and `'` are reserved for escaping them. Escaped output round-trips
+/// through [`expand_braces_with`](crate::expand_braces_with) using the same policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EscapePolicy {
+    /// Reject paths containing `{`, `}` or `,` (default).
+    ///
+    /// Braces are still accepted when `reprocess_braces` is enabled, as they
+    /// are expanded before compression.
+    #[default]
+    Reject,
+
+    /// Prefix each metacharacter with a backslash: `a,b.txt` → `a\,b.txt`.
+    Backslash,
+
+    /// Wrap segments containing metacharacters in single quotes: `a,b.txt` → `'a,b.txt'`.
+    Quote,
+
+    /// Quote segments for pasting into a POSIX shell: `my dir/a b.txt` → `'my dir'/'a b.txt'`.
+    ///
+    /// Any segment containing a character other than ASCII letters, digits or
+    /// `_-./:@%+=` is wrapped in single quotes, while the brace syntax itself is
+    /// left unquoted so that the shell still expands it.
+    Shell,
+
+    /// Wrap segments containing metacharacters in single quotes, doubling any
+    /// embedded quote: `it's,x` → `'it''s,x'`.
+    ///
+    /// Backslashes are always literal, so this is the policy used in Windows
+    /// path mode, where `\` is the separator.
+    QuoteDoubled,
+}
+
+impl EscapePolicy {
+    /// Whether output under this policy contains quotes (and possibly backslash escapes)
+    pub(crate) fn escapes(self) -> bool {
+        self != EscapePolicy::Reject
+    }
+
+    /// Whether a backslash makes the next character literal under this policy
+    pub(crate) fn backslash_escapes(self) -> bool {
+        self.escapes() && self != EscapePolicy::QuoteDoubled
+    }
+}
+
+/// Characters that must be escaped when they occur literally
+const METACHARS: &[char] = &['{', '}', ',', '\\', '\''];
+
+/// Characters that must be quoted when backslashes are literal
+const DOUBLED_METACHARS: &[char] = &['{', '}', ',', '\''];
+
+/// Characters that make a path ambiguous under [`EscapePolicy::Reject`]
+pub(crate) const REJECTED: &[char] = &['{', '}', ','];
+
+/// Escape literal text for inclusion in a braces expression
+pub fn escape_text(text: &str, policy: EscapePolicy) -> String {
+    match policy {
+        EscapePolicy::Reject => text.to_string(),
+        EscapePolicy::Backslash => {
+            let mut out = String::with_capacity(text.len());
+            for c in text.chars() {
+                if METACHARS.contains(&c) {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+            out
+        }
+        EscapePolicy::Quote if text.contains(METACHARS) => quote(text),
+        EscapePolicy::Shell if !text.chars().all(is_shell_safe) => quote(text),
+        EscapePolicy::QuoteDoubled if text.contains(DOUBLED_METACHARS) => {
+            format!("'{}'", text.replace('\'', "''"))
+        }
+        EscapePolicy::Quote | EscapePolicy::Shell | EscapePolicy::QuoteDoubled => text.to_string(),
+    }
+}
+
+/// Wrap text in single quotes, writing embedded quotes as `'\''`
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Characters a POSIX shell never treats specially in an unquoted word
+fn is_shell_safe(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_-./:@%+=".contains(c)
+}
+
+/// The text written as `text` under `policy`, with its escapes and quotes removed
+pub(crate) fn unescape(text: &str, policy: EscapePolicy) -> String {
+    if !policy.escapes() {
+        return text.to_string();
+    }
+    parse_braces(text, policy).expr.expand().concat()
+}
+
+/// Find the quote closing the quoted run opened at `open`, with the run's literal text
+///
+/// Returns `chars.len()` as the end of an unterminated run. Under
+/// [`EscapePolicy::QuoteDoubled`], `''` inside the run is a literal quote.
+pub(crate) fn quoted_run(chars: &[char], open: usize, policy: EscapePolicy) -> (usize, String) {
+    let mut text = String::new();
+    let mut i = open + 1;
+    while i < chars.len() {
+        if chars[i] == '\'' {
+            if policy == EscapePolicy::QuoteDoubled && chars.get(i + 1) == Some(&'\'') {
+                text.push('\'');
+                i += 2;
+                continue;
+            }
+            break;
+        }
+        text.push(chars[i]);
+        i += 1;
+    }
+    (i, text)
+}
+
+/// Find the index of the `}` closing the group opened at `open`
+///
+/// Escapes and quoted text are skipped as written under `policy`. Returns
+/// `None` for an unbalanced group.
+pub(crate) fn find_group_end(chars: &[char], open: usize, policy: EscapePolicy) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = open;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if policy.backslash_escapes() => i += 1,
+            '\'' if policy.escapes() => i = quoted_run(chars, i, policy).0,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split an expression into atoms that can be factored without breaking syntax
+///
+/// An atom is a single character, an escape sequence, a quoted run, or a whole
+/// brace group, so a common prefix or suffix made of atoms never cuts through
+/// an escape or a group.
+pub(crate) fn atoms(s: &str, policy: EscapePolicy) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let end = match chars[i] {
+            '\\' if policy.backslash_escapes() && i + 1 < chars.len() => i + 1,
+            '\'' if policy.escapes() => quoted_run(&chars, i, policy).0.min(chars.len() - 1),
+            '{' => find_group_end(&chars, i, policy).unwrap_or(i),
+            _ => i,
+        };
+        out.push(chars[i..=end].iter().collect());
+        i = end + 1;
+    }
+    out
+}
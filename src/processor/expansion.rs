@@ -1,5 +1,10 @@
-use super::normalise::{can_stem_split, find_common_string_prefix, find_common_string_suffix};
+use super::ast::BraceExpr;
+use super::direction::Direction;
+use super::escape::EscapePolicy;
+use super::parse::parse_braces;
+use super::style::PathStyle;
 use super::trie::Node;
+use super::url::shared_delimiter;
 use super::BraceConfig;
 use std::collections::{HashMap, HashSet};
 
@@ -10,7 +15,7 @@ pub fn compute_reprs(
     sep: &str,
     config: &BraceConfig,
 ) -> (
-    std::collections::HashMap<usize, String>,
+    std::collections::HashMap<usize, BraceExpr>,
     std::collections::HashMap<usize, Vec<String>>,
 ) {
     let mut stack = vec![root_idx];
@@ -31,277 +36,324 @@ pub fn compute_reprs(
         }
     }
 
-    let mut reprs = std::collections::HashMap::new();
+    let mut reprs: HashMap<usize, BraceExpr> = std::collections::HashMap::new();
     let mut raw_leaves: HashMap<usize, Vec<String>> = HashMap::new();
 
     for &idx in &post {
-        let node = &nodes[idx];
+        let (repr, node_raws) =
+            compose_node(nodes, idx, root_idx, sep, config, &reprs, &raw_leaves);
+        raw_leaves.insert(idx, node_raws);
+        reprs.insert(idx, repr);
+    }
 
-        let mut child_repr_items = vec![];
-        let mut child_raws = vec![];
+    (reprs, raw_leaves)
+}
 
-        for (child_label, child_idx) in node.children.iter() {
-            let label_str = &child_label.0;
+/// Compute the representation and raw leaves of one node from those of its children
+///
+/// Every child must already have an entry in `reprs` and `raw_leaves`. The
+/// result depends on nothing else outside the node's subtree, so it can be
+/// kept until the subtree changes.
+pub(crate) fn compose_node(
+    nodes: &[Node],
+    idx: usize,
+    root_idx: usize,
+    sep: &str,
+    config: &BraceConfig,
+    reprs: &HashMap<usize, BraceExpr>,
+    raw_leaves: &HashMap<usize, Vec<String>>,
+) -> (BraceExpr, Vec<String>) {
+    let node = &nodes[idx];
 
-            // Get the child's representation if it exists, otherwise use the label
-            child_repr_items.push(
-                reprs
-                    .get(child_idx)
-                    .cloned()
-                    .unwrap_or_else(|| label_str.clone()),
-            );
+    let has_trailing_sep_child = node
+        .children
+        .values()
+        .any(|&child_idx| nodes[child_idx].is_trailing_sep);
 
-            if let Some(r) = raw_leaves.get(child_idx) {
-                child_raws.extend(r.clone());
-            } else {
-                child_raws.push(label_str.clone());
-            }
+    // An empty segment below the root keeps its separator, as in `a//b`
+    let compose = |items: &[BraceExpr]| {
+        // A directory whose only entry is its trailing separator keeps it, as in `dir/`
+        if idx != root_idx
+            && !node.is_leaf
+            && has_trailing_sep_child
+            && items.len() == 1
+            && items[0].is_empty()
+        {
+            return config
+                .direction
+                .join_expr(&node.label, sep, BraceExpr::empty());
         }
+        let composed = compose_label_and_items(
+            &node.label,
+            sep,
+            items,
+            config.max_brace_size,
+            config.sort_items,
+            config.direction,
+        );
+        if idx != root_idx && node.label.is_empty() && !composed.is_empty() {
+            config.direction.join_expr("", sep, composed)
+        } else {
+            composed
+        }
+    };
 
-        // Compute raw leaves for this node
-        let node_raws: Vec<String> = if node.children.is_empty() {
-            if node.label.is_empty() {
-                vec![String::new()]
-            } else {
-                vec![node.label.clone()]
-            }
+    let mut child_repr_items = vec![];
+    let mut child_raws = vec![];
+
+    for (child_label, child_idx) in node.children.iter() {
+        let label_str = &child_label.0;
+
+        // Get the child's representation if it exists, otherwise use the label
+        child_repr_items.push(
+            reprs
+                .get(child_idx)
+                .cloned()
+                .unwrap_or_else(|| BraceExpr::Literal(label_str.clone())),
+        );
+
+        if let Some(r) = raw_leaves.get(child_idx) {
+            child_raws.extend(r.clone());
         } else {
-            let mut out = vec![];
-            for r in child_raws.iter() {
-                if node.label.is_empty() {
-                    out.push(r.clone())
-                } else if r.is_empty() {
-                    out.push(node.label.clone())
-                } else {
-                    out.push(format!("{}{}{}", node.label, sep, r));
-                }
-            }
-            // Don't add node.label again if we already have a trailing sep child
-            let has_trailing_sep_child = node
-                .children
-                .iter()
-                .any(|(_, child_idx)| nodes[*child_idx].is_trailing_sep);
-            if node.is_leaf && !has_trailing_sep_child {
-                out.push(node.label.clone())
+            child_raws.push(label_str.clone());
+        }
+    }
+
+    // Compute raw leaves for this node
+    let node_raws: Vec<String> = if node.children.is_empty() {
+        if node.label.is_empty() {
+            vec![String::new()]
+        } else {
+            vec![node.label.clone()]
+        }
+    } else {
+        let mut out = vec![];
+        for r in child_raws.iter() {
+            if idx == root_idx {
+                out.push(r.clone())
+            } else if r.is_empty() {
+                // A trailing separator
+                out.push(config.direction.join(&node.label, sep, ""))
+            } else {
+                out.push(config.direction.join(&node.label, sep, r));
             }
-            out
-        };
-        raw_leaves.insert(idx, node_raws.clone());
+        }
+        // Don't add node.label again if we already have a trailing sep child
+        if node.is_leaf && !has_trailing_sep_child {
+            out.push(node.label.clone())
+        }
+        out
+    };
 
-        // Compose final representation
-        let repr = if node.depth > config.max_depth {
-            // depth limit: use raw leaves
-            let suffixes: Vec<String> = node_raws
+    // Alternatives of whole raw paths, when empty alternatives are disallowed
+    let raw_alternatives = || {
+        BraceExpr::Alt(
+            node_raws
                 .iter()
-                .map(|s| {
-                    if node.label.is_empty() {
-                        s.clone()
-                    } else if s.starts_with(&format!("{}{}", node.label, sep)) {
-                        s[node.label.len() + sep.len()..].to_string()
-                    } else if s == &node.label {
-                        String::new()
-                    } else {
-                        s.clone()
-                    }
+                .map(|r| BraceExpr::Literal(r.clone()))
+                .collect(),
+        )
+    };
+
+    // Compose final representation
+    let repr = if node.depth > config.max_depth {
+        // depth limit: use raw leaves
+        let suffixes: Vec<BraceExpr> = node_raws
+            .iter()
+            .map(|s| {
+                BraceExpr::Literal(if idx == root_idx {
+                    s.clone()
+                } else if let Some(rest) = config.direction.strip_label(s, &node.label, sep) {
+                    rest.to_string()
+                } else if s == &node.label {
+                    String::new()
+                } else {
+                    s.clone()
                 })
-                .collect();
+            })
+            .collect();
 
-            if config.disallow_empty_braces
-                && suffixes.iter().any(|s| s.is_empty())
-                && suffixes.len() > 1
-            {
-                format!("{{{}}}", node_raws.join(","))
-            } else {
-                compose_label_and_items(
-                    &node.label,
-                    sep,
-                    &suffixes,
-                    config.max_brace_size,
-                    config.sort_items,
-                )
-            }
+        if config.disallow_empty_braces
+            && suffixes.iter().any(BraceExpr::is_empty)
+            && suffixes.len() > 1
+        {
+            raw_alternatives()
         } else {
-            let mut items = child_repr_items.clone();
+            compose(&suffixes)
+        }
+    } else {
+        let mut items = child_repr_items.clone();
 
-            // Only add empty string if this node is a leaf AND doesn't have a trailing sep child
-            let has_trailing_sep_child = node
-                .children
-                .iter()
-                .any(|(_, child_idx)| nodes[*child_idx].is_trailing_sep);
+        // Add empty string for leaf nodes that don't have trailing sep children
+        // This represents the case where path ends at this node (e.g., "a" in ["a", "a/b"])
+        if node.is_leaf && !node.is_trailing_sep && !has_trailing_sep_child {
+            items.push(BraceExpr::empty())
+        }
 
-            // Add empty string for leaf nodes that don't have trailing sep children
-            // This represents the case where path ends at this node (e.g., "a" in ["a", "a/b"])
-            if node.is_leaf && !node.is_trailing_sep && !has_trailing_sep_child {
-                items.push(String::new())
-            }
+        // For nodes with trailing sep children, the empty string is already in items
+        // from the child processing above
 
-            // For nodes with trailing sep children, the empty string is already in items
-            // from the child processing above
+        // Factoring works on atoms, so no escape or brace group is cut
+        let item_atoms: Vec<Vec<BraceExpr>> =
+            items.iter().map(|item| item.atoms(config.escape)).collect();
+        let (shared_prefix, shared_suffix) = shared_atoms(&item_atoms);
 
-            if config.disallow_empty_braces && items.iter().any(|s| s.is_empty()) && items.len() > 1
-            {
-                format!("{{{}}}", node_raws.join(","))
-            } else if config.allow_stem_split && can_stem_split(&items) {
-                let prefix = find_common_string_prefix(&items);
-                let suffix = find_common_string_suffix(&items);
-                let mut vars = items
-                    .iter()
-                    .map(|s| {
-                        s.strip_prefix(&prefix)
-                            .unwrap_or(s)
-                            .strip_suffix(&suffix)
-                            .unwrap_or(s)
-                            .to_string()
-                    })
-                    .collect::<Vec<_>>();
-                if config.sort_items {
-                    vars.sort();
-                }
-                let inner = if vars.len() == 1 {
-                    vars[0].clone()
-                } else {
-                    format!("{{{}}}", vars.join(","))
-                };
-                if node.label.is_empty() {
-                    format!("{}{}{}", prefix, inner, suffix)
-                } else if !prefix.is_empty() || !suffix.is_empty() {
-                    format!("{}{}{}{}{}", node.label, sep, prefix, inner, suffix)
-                } else {
-                    format!("{}{}{}", node.label, sep, inner)
-                }
+        // In stable mode only groups of plain leaves are factored, so that a
+        // change deeper in the tree cannot alter how its ancestors are split
+        let plain_leaves = !node.is_leaf
+            && node.children.values().all(|&child_idx| {
+                nodes[child_idx].children.is_empty() && !nodes[child_idx].is_trailing_sep
+            });
+        let factors = if config.allow_stem_split
+            && (!config.stable_output || plain_leaves)
+            && items.len() > 1
+            && (shared_prefix > 0 || shared_suffix > 0)
+        {
+            Some((shared_prefix, shared_suffix))
+        } else if config.stable_output && plain_leaves && items.len() > 1 {
+            let shorter = item_atoms.iter().all(|a| a.len() > shared_suffix);
+            (shared_suffix > 0 && shorter).then_some((0, shared_suffix))
+        } else if config.path_style == PathStyle::Url && items.len() > 1 {
+            let texts: Vec<String> = items.iter().map(ToString::to_string).collect();
+            shared_delimiter(&texts).map(|_| (1, 0))
+        } else {
+            None
+        };
+
+        if config.disallow_empty_braces && items.iter().any(BraceExpr::is_empty) && items.len() > 1
+        {
+            raw_alternatives()
+        } else if let Some((prefix_len, suffix_len)) = factors {
+            let first = &item_atoms[0];
+            let prefix = BraceExpr::concat(first[..prefix_len].iter().cloned());
+            let suffix = BraceExpr::concat(first[first.len() - suffix_len..].iter().cloned());
+            let vars: Vec<BraceExpr> = item_atoms
+                .iter()
+                .map(|a| BraceExpr::concat(a[prefix_len..a.len() - suffix_len].iter().cloned()))
+                .collect();
+            let inner = compose_label_and_items(
+                "",
+                sep,
+                &vars,
+                config.max_brace_size,
+                config.sort_items,
+                config.direction,
+            );
+            let body = BraceExpr::concat([prefix, inner, suffix]);
+            if idx == root_idx {
+                body
             } else {
-                compose_label_and_items(
-                    &node.label,
-                    sep,
-                    &items,
-                    config.max_brace_size,
-                    config.sort_items,
-                )
+                config.direction.join_expr(&node.label, sep, body)
             }
-        };
+        } else {
+            compose(&items)
+        }
+    };
 
-        reprs.insert(idx, repr);
-    }
+    (repr, node_raws)
+}
 
-    (reprs, raw_leaves)
+/// Numbers of leading and trailing atoms shared by every item
+///
+/// The two never overlap, so each item keeps at least its shared prefix and
+/// suffix. Atoms are compared by their written form.
+fn shared_atoms(items: &[Vec<BraceExpr>]) -> (usize, usize) {
+    let Some(first) = items.first() else {
+        return (0, 0);
+    };
+    let texts: Vec<Vec<String>> = items
+        .iter()
+        .map(|a| a.iter().map(ToString::to_string).collect())
+        .collect();
+    let shortest = items.iter().map(Vec::len).min().unwrap_or(0);
+
+    let prefix = (0..shortest)
+        .take_while(|&i| texts.iter().all(|t| t[i] == texts[0][i]))
+        .count();
+    let suffix = (1..=shortest - prefix)
+        .take_while(|&i| {
+            let at = first.len() - i;
+            texts.iter().all(|t| t[t.len() - i] == texts[0][at])
+        })
+        .count();
+    (prefix, suffix)
 }
 
-/// Compose node label + separator + items into string
-fn compose_label_and_items(
+/// Compose node label + separator + items into an expression (items first, right to left)
+pub fn compose_label_and_items(
     label: &str,
     sep: &str,
-    items: &[String],
+    items: &[BraceExpr],
     max_brace_size: Option<usize>,
     sort_items: bool,
-) -> String {
-    let mut cleaned: Vec<String> = items.to_vec();
+    direction: Direction,
+) -> BraceExpr {
+    let mut cleaned: Vec<BraceExpr> = items.to_vec();
     if sort_items {
-        cleaned.sort();
+        direction.sort(&mut cleaned, sep);
     }
 
-    let compose_inner = |slice: &[String]| {
-        // Handle empty slice
-        if slice.is_empty() {
-            String::new()
-        } else if slice.len() == 1 {
-            // Don't wrap single empty strings in braces
-            if slice[0].is_empty() {
-                String::new()
-            } else {
-                slice[0].clone()
-            }
-        } else {
-            format!("{{{}}}", slice.join(","))
+    let compose_inner = |slice: &[BraceExpr]| {
+        // Handle empty slice; a single (possibly empty) item needs no braces
+        match slice.len() {
+            0 => BraceExpr::empty(),
+            1 => slice[0].clone(),
+            _ => BraceExpr::Alt(slice.to_vec()),
         }
     };
 
-    if let Some(max) = max_brace_size {
-        if cleaned.len() <= max {
-            if label.is_empty() {
-                compose_inner(&cleaned)
-            } else {
-                // Don't add separator if inner is empty
-                let inner = compose_inner(&cleaned);
-                if inner.is_empty() {
-                    label.to_string()
-                } else {
-                    format!("{}{}{}", label, sep, inner)
-                }
-            }
-        } else {
-            let mut groups = vec![];
-            for chunk in cleaned.chunks(max) {
-                if label.is_empty() {
-                    groups.push(compose_inner(chunk))
-                } else {
-                    // Apply same fix here
-                    let inner = compose_inner(chunk);
-                    if inner.is_empty() {
-                        groups.push(label.to_string())
-                    } else {
-                        groups.push(format!("{}{}{}", label, sep, inner))
-                    }
-                }
-            }
-            format!("{{{}}}", groups.join(","))
-        }
-    } else if label.is_empty() {
-        compose_inner(&cleaned)
-    } else {
-        // Don't add separator if inner is empty
-        let inner = compose_inner(&cleaned);
-        if inner.is_empty() {
-            label.to_string()
+    // Don't add separator if inner is empty
+    let labelled = |inner: BraceExpr| {
+        if label.is_empty() {
+            inner
+        } else if inner.is_empty() {
+            BraceExpr::Literal(label.to_string())
         } else {
-            format!("{}{}{}", label, sep, inner)
+            direction.join_expr(label, sep, inner)
         }
+    };
+
+    match max_brace_size {
+        Some(max) if cleaned.len() > max => BraceExpr::Alt(
+            cleaned
+                .chunks(max)
+                .map(|chunk| labelled(compose_inner(chunk)))
+                .collect(),
+        ),
+        _ => labelled(compose_inner(&cleaned)),
     }
 }
 
 /// Expand braces (a{b,c}d -> abd, acd)
+///
+/// Backslashes and quotes are taken literally; see [`expand_braces_with`]
+/// for expressions produced with an [`EscapePolicy`]. An unbalanced brace
+/// is read as a literal, as reported by [`parse_braces`](crate::parse_braces).
 pub fn expand_braces(pattern: &str) -> Vec<String> {
     if !pattern.contains('{') {
         return vec![pattern.to_string()];
     }
-    let mut results = vec![String::new()];
-    let mut chars = pattern.chars().peekable();
-    let mut current = String::new();
-    while let Some(ch) = chars.next() {
-        if ch == '{' {
-            let mut depth = 1;
-            let mut inner = String::new();
-            for c in chars.by_ref() {
-                if c == '{' {
-                    depth += 1;
-                    inner.push(c);
-                } else if c == '}' {
-                    depth -= 1;
-                    if depth == 0 {
-                        break;
-                    }
-                    inner.push(c);
-                } else {
-                    inner.push(c);
-                }
-            }
-            let options: Vec<&str> = inner.split(',').collect();
-            let mut new_results = vec![];
-            for r in &results {
-                for opt in &options {
-                    let mut tmp = r.clone();
-                    tmp.push_str(&current);
-                    tmp.push_str(opt);
-                    new_results.push(tmp);
-                }
-            }
-            results = new_results;
-            current.clear();
-        } else {
-            current.push(ch);
-        }
-    }
-    for r in &mut results {
-        r.push_str(&current);
+    parse_braces(pattern, EscapePolicy::Reject).expr.expand()
+}
+
+/// Expand braces, honouring the escapes written by an [`EscapePolicy`]
+///
+/// With [`EscapePolicy::Backslash`] or [`EscapePolicy::Quote`], a backslash
+/// makes the next character literal and single quotes enclose literal text,
+/// as in a POSIX shell. With [`EscapePolicy::QuoteDoubled`], backslashes are
+/// literal and `''` inside quotes is a quote. With [`EscapePolicy::Reject`]
+/// this is [`expand_braces`].
+///
+/// # Example
+/// ```
+/// use braces::{expand_braces_with, EscapePolicy};
+///
+/// let paths = expand_braces_with(r"{a\,b,'c,d'}.txt", EscapePolicy::Backslash);
+/// assert_eq!(paths, vec!["a,b.txt", "c,d.txt"]);
+/// ```
+pub fn expand_braces_with(pattern: &str, escape: EscapePolicy) -> Vec<String> {
+    if !escape.escapes() {
+        return expand_braces(pattern);
     }
-    results
+    parse_braces(pattern, escape).expr.expand()
 }
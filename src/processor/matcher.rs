@@ -0,0 +1,451 @@
+//! Testing paths against a brace expression without expanding it
+
+use super::canonical::parse;
+use super::escape::{quoted_run, EscapePolicy};
+use super::parse::{SpannedExpr, SpannedKind};
+use super::range::BraceRange;
+use super::{resolve_style, BraceConfig, CaseFolding};
+use crate::error::Result;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A brace expression compiled for testing paths against it
+///
+/// The expression is compiled into an automaton with one state per
+/// character, so testing a path takes time linear in its length however
+/// many paths the expression expands to. Sequences such as `{1..100}` are
+/// matched by digit classes rather than by listing their items, except
+/// for sequences with a step.
+///
+/// The expression is read with `config.escape`, and paths are compared
+/// case-insensitively when `config.case_folding` folds case. Paths are
+/// matched as written: they are not normalised first.
+///
+/// # Example
+/// ```
+/// use braces::{BraceConfig, BraceMatcher};
+///
+/// let matcher = BraceMatcher::new("src/{a,b/{c..z}}.rs", &BraceConfig::default()).unwrap();
+/// assert!(matcher.is_match("src/b/q.rs"));
+/// assert!(!matcher.is_match("src/b/a.rs"));
+///
+/// let found = matcher.find("src/b/q.rs").unwrap();
+/// let taken: Vec<_> = found.choices.iter().map(|c| (c.group.clone(), c.alternative)).collect();
+/// assert_eq!(taken, vec![(4..16, 1), (9..15, 14)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BraceMatcher {
+    states: Vec<State>,
+    start: usize,
+    /// Span and sequence, if it is one, of each group by number
+    groups: Vec<(Range<usize>, Option<BraceRange>)>,
+    case_folding: CaseFolding,
+}
+
+/// How a path matched a [`BraceMatcher`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BraceMatch {
+    /// The alternative taken in each group the path passed through, in order.
+    pub choices: Vec<GroupChoice>,
+}
+
+/// The alternative a path took through one group of the expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupChoice {
+    /// Byte range of the group in the expression, including its braces.
+    pub group: Range<usize>,
+
+    /// Index of the alternative taken, or of the item of a sequence.
+    pub alternative: usize,
+
+    /// Byte range of the path matched by the alternative.
+    pub matched: Range<usize>,
+}
+
+/// A state of the automaton
+#[derive(Debug, Clone)]
+enum State {
+    /// Consume one character of the class
+    Step(Class, usize),
+    /// Continue from each target, in order of preference
+    Split(Vec<usize>),
+    /// Enter an alternative of a numbered group
+    Enter {
+        group: usize,
+        alternative: usize,
+        next: usize,
+    },
+    /// Leave a numbered group
+    Leave { group: usize, next: usize },
+    /// The whole expression has matched
+    Accept,
+}
+
+/// The characters a [`State::Step`] consumes
+#[derive(Debug, Clone)]
+enum Class {
+    Char(char),
+    Between(char, char),
+    /// Any character; `*` and `?` do not match the first character of the separator.
+    Any {
+        separator: Option<char>,
+    },
+}
+
+/// A piece of literal text, read as glob syntax or not
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Atom {
+    Char(char),
+    /// `?`: one character within a segment.
+    One,
+    /// `*`: any characters within a segment.
+    Star,
+    /// `**`: any characters, across separators.
+    Globstar,
+}
+
+/// A path being matched, with the groups it has passed through
+#[derive(Debug, Clone)]
+struct Thread {
+    state: usize,
+    choices: Vec<GroupChoice>,
+}
+
+impl BraceMatcher {
+    /// Compile an expression, reading all of its text literally
+    ///
+    /// Returns [`BraceError::InvalidBraceSyntax`](crate::BraceError::InvalidBraceSyntax)
+    /// if the expression cannot be parsed.
+    pub fn new(expr: &str, config: &BraceConfig) -> Result<Self> {
+        Self::compile(expr, config, false)
+    }
+
+    /// Compile an expression whose unescaped `*`, `**` and `?` are wildcards
+    ///
+    /// `*` matches any characters within a segment, `?` any one character
+    /// within a segment, and `**` any characters including separators. `**`
+    /// followed by the separator also matches no segments at all, so
+    /// `src/**/*.rs` matches `src/lib.rs`.
+    ///
+    /// # Example
+    /// ```
+    /// use braces::{BraceConfig, BraceMatcher};
+    ///
+    /// let matcher = BraceMatcher::glob("src/{a,b/{c..z}}/*.rs", &BraceConfig::default()).unwrap();
+    /// assert!(matcher.is_match("src/a/lib.rs"));
+    /// assert!(matcher.is_match("src/b/x/main.rs"));
+    /// assert!(!matcher.is_match("src/a/nested/lib.rs"));
+    /// ```
+    pub fn glob(expr: &str, config: &BraceConfig) -> Result<Self> {
+        Self::compile(expr, config, true)
+    }
+
+    fn compile(expr: &str, config: &BraceConfig, glob: bool) -> Result<Self> {
+        let config = resolve_style(config);
+        let tree = parse(expr, &config)?;
+
+        let mut compiler = Compiler {
+            source: expr,
+            escape: config.escape,
+            glob,
+            separator: config.path_separator.clone(),
+            states: vec![State::Accept],
+            groups: vec![],
+            numbers: HashMap::new(),
+        };
+        compiler.number(&tree);
+        let start = compiler.node(&tree, 0);
+
+        Ok(BraceMatcher {
+            states: compiler.states,
+            start,
+            groups: compiler.groups,
+            case_folding: config.case_folding,
+        })
+    }
+
+    /// Whether `path` is one of the paths the expression expands to
+    pub fn is_match(&self, path: &str) -> bool {
+        self.run(path, false).is_some()
+    }
+
+    /// How `path` matched, or `None` if it did not
+    ///
+    /// Where a path can match in more than one way, the earliest alternatives
+    /// are preferred, as in expansion order.
+    pub fn find(&self, path: &str) -> Option<BraceMatch> {
+        let mut choices = self.run(path, true)?;
+        for choice in &mut choices {
+            // The item of a sequence is known only from the text it matched
+            let group = self.groups.iter().find(|(span, _)| *span == choice.group);
+            if let Some(range) = group.and_then(|(_, range)| *range) {
+                let text = &path[choice.matched.clone()];
+                choice.alternative = range
+                    .position(text)
+                    .or_else(|| range.position(&text.to_lowercase()))
+                    .unwrap_or(0);
+            }
+        }
+        Some(BraceMatch { choices })
+    }
+
+    /// Simulate the automaton over `path`, returning the choices of the preferred match
+    ///
+    /// Choices are recorded only when `track` is set.
+    fn run(&self, path: &str, track: bool) -> Option<Vec<GroupChoice>> {
+        let mut current = vec![];
+        let mut seen = vec![false; self.states.len()];
+        self.add(&mut current, &mut seen, self.start, vec![], 0, track);
+
+        for (at, c) in path.char_indices() {
+            if current.is_empty() {
+                return None;
+            }
+            let after = at + c.len_utf8();
+            let mut next = vec![];
+            seen.iter_mut().for_each(|s| *s = false);
+            for thread in current {
+                if let State::Step(class, target) = &self.states[thread.state] {
+                    if self.accepts(class, c) {
+                        self.add(&mut next, &mut seen, *target, thread.choices, after, track);
+                    }
+                }
+            }
+            current = next;
+        }
+
+        current
+            .into_iter()
+            .find(|thread| matches!(self.states[thread.state], State::Accept))
+            .map(|thread| thread.choices)
+    }
+
+    /// Add a thread at `state`, following every transition that consumes nothing
+    fn add(
+        &self,
+        threads: &mut Vec<Thread>,
+        seen: &mut [bool],
+        state: usize,
+        mut choices: Vec<GroupChoice>,
+        at: usize,
+        track: bool,
+    ) {
+        if seen[state] {
+            return;
+        }
+        seen[state] = true;
+        match &self.states[state] {
+            State::Split(targets) => {
+                for &target in targets {
+                    self.add(threads, seen, target, choices.clone(), at, track);
+                }
+            }
+            State::Enter {
+                group,
+                alternative,
+                next,
+            } => {
+                if track {
+                    choices.push(GroupChoice {
+                        group: self.groups[*group].0.clone(),
+                        alternative: *alternative,
+                        matched: at..at,
+                    });
+                }
+                self.add(threads, seen, *next, choices, at, track);
+            }
+            State::Leave { group, next } => {
+                let span = &self.groups[*group].0;
+                if let Some(choice) = choices.iter_mut().rev().find(|c| &c.group == span) {
+                    choice.matched.end = at;
+                }
+                self.add(threads, seen, *next, choices, at, track);
+            }
+            State::Step(..) | State::Accept => threads.push(Thread { state, choices }),
+        }
+    }
+
+    /// Whether a character class accepts `c`
+    fn accepts(&self, class: &Class, c: char) -> bool {
+        let fold = |c: char| match self.case_folding {
+            CaseFolding::Off => c,
+            _ => c.to_lowercase().next().unwrap_or(c),
+        };
+        match *class {
+            Class::Char(expected) => expected == c || fold(expected) == fold(c),
+            Class::Between(low, high) => {
+                // Any case of `c` in the range will do, as `{A..C}` accepts `b`
+                let range = low..=high;
+                range.contains(&c)
+                    || self.case_folding != CaseFolding::Off
+                        && (range.contains(&fold(c))
+                            || c.to_uppercase().next().is_some_and(|u| range.contains(&u)))
+            }
+            Class::Any { separator } => separator != Some(c),
+        }
+    }
+}
+
+/// Builds the automaton back to front, each node leading to a known next state
+struct Compiler<'a> {
+    source: &'a str,
+    escape: EscapePolicy,
+    glob: bool,
+    separator: String,
+    states: Vec<State>,
+    groups: Vec<(Range<usize>, Option<BraceRange>)>,
+    /// Group number by the start of its span
+    numbers: HashMap<usize, usize>,
+}
+
+impl Compiler<'_> {
+    fn push(&mut self, state: State) -> usize {
+        self.states.push(state);
+        self.states.len() - 1
+    }
+
+    /// Number the groups of `expr` in source order
+    fn number(&mut self, expr: &SpannedExpr) {
+        match &expr.kind {
+            SpannedKind::Concat(parts) | SpannedKind::Alt(parts) => {
+                if let SpannedKind::Alt(_) = expr.kind {
+                    self.numbers.insert(expr.span.start, self.groups.len());
+                    self.groups.push((expr.span.clone(), None));
+                }
+                parts.iter().for_each(|part| self.number(part));
+            }
+            SpannedKind::Range(range) => {
+                self.numbers.insert(expr.span.start, self.groups.len());
+                self.groups.push((expr.span.clone(), Some(*range)));
+            }
+            SpannedKind::Literal(_) | SpannedKind::Trivia => {}
+        }
+    }
+
+    /// Compile `expr` to states leading to `next`, returning its first state
+    fn node(&mut self, expr: &SpannedExpr, next: usize) -> usize {
+        match &expr.kind {
+            SpannedKind::Trivia => next,
+            SpannedKind::Literal(text) => {
+                let atoms = if self.glob {
+                    glob_atoms(&self.source[expr.span.clone()], self.escape)
+                } else {
+                    text.chars().map(Atom::Char).collect()
+                };
+                self.atoms(&atoms, next)
+            }
+            SpannedKind::Concat(parts) => parts
+                .iter()
+                .rev()
+                .fold(next, |next, part| self.node(part, next)),
+            SpannedKind::Alt(options) => {
+                let group = self.numbers[&expr.span.start];
+                let leave = self.push(State::Leave { group, next });
+                let targets = options
+                    .iter()
+                    .enumerate()
+                    .map(|(alternative, option)| {
+                        let next = self.node(option, leave);
+                        self.push(State::Enter {
+                            group,
+                            alternative,
+                            next,
+                        })
+                    })
+                    .collect();
+                self.push(State::Split(targets))
+            }
+            SpannedKind::Range(range) => {
+                let group = self.numbers[&expr.span.start];
+                let leave = self.push(State::Leave { group, next });
+                let targets = match range.classes() {
+                    Some(patterns) => patterns
+                        .iter()
+                        .map(|pattern| {
+                            pattern.iter().rev().fold(leave, |next, &(low, high)| {
+                                self.push(State::Step(Class::Between(low, high), next))
+                            })
+                        })
+                        .collect(),
+                    None => range
+                        .items()
+                        .map(|item| {
+                            let atoms: Vec<Atom> = item.chars().map(Atom::Char).collect();
+                            self.atoms(&atoms, leave)
+                        })
+                        .collect(),
+                };
+                let split = self.push(State::Split(targets));
+                self.push(State::Enter {
+                    group,
+                    alternative: 0,
+                    next: split,
+                })
+            }
+        }
+    }
+
+    /// Compile literal text and wildcards leading to `next`
+    fn atoms(&mut self, atoms: &[Atom], next: usize) -> usize {
+        let separator: Vec<Atom> = self.separator.chars().map(Atom::Char).collect();
+        let first = self.separator.chars().next();
+        // The first state of each suffix of `atoms`
+        let mut starts = vec![next; atoms.len() + 1];
+        for i in (0..atoms.len()).rev() {
+            let next = starts[i + 1];
+            starts[i] = match atoms[i] {
+                Atom::Char(c) => self.push(State::Step(Class::Char(c), next)),
+                Atom::One => self.push(State::Step(Class::Any { separator: first }, next)),
+                Atom::Star => self.repeat(Class::Any { separator: first }, next),
+                Atom::Globstar => {
+                    let any = self.repeat(Class::Any { separator: None }, next);
+                    if !separator.is_empty() && atoms[i + 1..].starts_with(&separator) {
+                        // `**/` may also match no segments at all
+                        let skip = starts[i + 1 + separator.len()];
+                        self.push(State::Split(vec![any, skip]))
+                    } else {
+                        any
+                    }
+                }
+            };
+        }
+        starts[0]
+    }
+
+    /// Any number of characters of `class`, as many as possible, then `next`
+    fn repeat(&mut self, class: Class, next: usize) -> usize {
+        let split = self.push(State::Split(vec![]));
+        let step = self.push(State::Step(class, split));
+        self.states[split] = State::Split(vec![step, next]);
+        split
+    }
+}
+
+/// Read literal text as written under `escape`, with unescaped wildcards
+fn glob_atoms(text: &str, escape: EscapePolicy) -> Vec<Atom> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if escape.backslash_escapes() && i + 1 < chars.len() => {
+                out.push(Atom::Char(chars[i + 1]));
+                i += 1;
+            }
+            '\'' if escape.escapes() => {
+                let (end, quoted) = quoted_run(&chars, i, escape);
+                out.extend(quoted.chars().map(Atom::Char));
+                i = end;
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                out.push(Atom::Globstar);
+                i += 1;
+            }
+            '*' => out.push(Atom::Star),
+            '?' => out.push(Atom::One),
+            c => out.push(Atom::Char(c)),
+        }
+        i += 1;
+    }
+    out
+}
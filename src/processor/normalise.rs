@@ -1,23 +1,59 @@
+use super::style::PathStyle;
+use super::windows::windows_root;
+use super::BraceConfig;
 use crate::error::{BraceError, Result};
 use std::collections::HashSet;
 
-/// Stem splitting eligibility
-pub fn can_stem_split(items: &[String]) -> bool {
-    if items.len() < 2 {
-        return false;
+/// Find separator occurrences, taking the longest match at each position
+///
+/// `sep` is always recognised alongside `separators`, so with `::` and `:` both
+/// in the set, `a:::b` holds `::` followed by `:`. Returns byte offsets.
+pub fn find_separators<'a>(
+    path: &str,
+    sep: &'a str,
+    separators: &'a [String],
+) -> Vec<(usize, &'a str)> {
+    let mut found = vec![];
+    let mut i = 0;
+    while i < path.len() {
+        let rest = &path[i..];
+        let longest = separators
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(sep))
+            .filter(|s| !s.is_empty() && rest.starts_with(s))
+            .max_by_key(|s| s.len());
+        match longest {
+            Some(s) => {
+                found.push((i, s));
+                i += s.len();
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    found
+}
+
+/// Split a path into segments at the occurrences of `sep` found by [`find_separators`]
+pub fn split_segments<'p>(path: &'p str, sep: &str, separators: &[String]) -> Vec<&'p str> {
+    let mut segments = vec![];
+    let mut start = 0;
+    for (at, found) in find_separators(path, sep, separators) {
+        if found == sep {
+            segments.push(&path[start..at]);
+            start = at + found.len();
+        }
     }
-    let p = find_common_string_prefix(items);
-    let s = find_common_string_suffix(items);
-    !p.is_empty() || !s.is_empty()
+    segments.push(&path[start..]);
+    segments
 }
 
 /// Validate separators
-pub fn validate_separators(paths: &[String], expected: &str) -> Result<()> {
-    let seps = vec!["/", "\\", ":"];
+pub fn validate_separators(paths: &[String], expected: &str, separators: &[String]) -> Result<()> {
     let mut found = HashSet::new();
     for path in paths {
-        for sep in &seps {
-            if path.contains(sep) && !expected.contains(sep) {
+        for (_, sep) in find_separators(path, expected, separators) {
+            if sep != expected {
                 found.insert(sep.to_string());
             }
         }
@@ -32,14 +68,15 @@ pub fn validate_separators(paths: &[String], expected: &str) -> Result<()> {
 }
 
 /// Normalize separators
-pub fn normalise_separators(path: &str, target: &str) -> String {
-    let seps = vec!["/", "\\", ":"];
-    let mut res = path.to_string();
-    for sep in seps {
-        if sep != target {
-            res = res.replace(sep, target);
-        }
+pub fn normalise_separators(path: &str, target: &str, separators: &[String]) -> String {
+    let mut res = String::with_capacity(path.len());
+    let mut start = 0;
+    for (at, sep) in find_separators(path, target, separators) {
+        res.push_str(&path[start..at]);
+        res.push_str(target);
+        start = at + sep.len();
     }
+    res.push_str(&path[start..]);
     res
 }
 
@@ -54,7 +91,8 @@ pub fn find_common_suffix(strings: &[String]) -> String {
         let c = first[i];
         for s in strings.iter().skip(1) {
             let sc: Vec<char> = s.chars().collect();
-            if sc.len() <= i || sc[sc.len() - first.len() + i] != c {
+            let from_end = first.len() - i;
+            if sc.len() < from_end || sc[sc.len() - from_end] != c {
                 break 'outer;
             }
         }
@@ -63,37 +101,128 @@ pub fn find_common_suffix(strings: &[String]) -> String {
     common.into_iter().collect()
 }
 
-/// Common prefix
-pub fn find_common_string_prefix(strings: &[String]) -> String {
-    if strings.is_empty() {
-        return String::new();
+/// Lexical path normalisation steps, applied before building the trie
+///
+/// Each step is a separate switch, so for example repeated separators can be
+/// collapsed while `.` and `..` segments are kept as written. Normalisation is
+/// purely lexical: no filesystem access is made and symlinks are not resolved.
+/// A trailing separator is always preserved, as it marks a directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Normalisation {
+    /// Collapse repeated separators: `src//b.rs` → `src/b.rs`.
+    ///
+    /// A leading double separator is kept, as POSIX leaves its meaning to the
+    /// implementation (and it starts a UNC path on Windows).
+    pub collapse_separators: bool,
+
+    /// Remove `.` segments: `src/./a.rs` → `src/a.rs`.
+    ///
+    /// A leading `./` is governed by `leading_dot` instead.
+    pub remove_dot_segments: bool,
+
+    /// Resolve `..` against the preceding segment: `src/x/../c.rs` → `src/c.rs`.
+    ///
+    /// Only resolved when the preceding segment is a plain name, so leading
+    /// `..` segments and those following the root are kept.
+    pub resolve_parent_segments: bool,
+
+    /// What to do with a leading `./` (default: [`LeadingDot::Keep`]).
+    pub leading_dot: LeadingDot,
+}
+
+/// Treatment of a leading `./` on relative paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LeadingDot {
+    /// Keep a leading `./` where present (default).
+    #[default]
+    Keep,
+
+    /// Remove any leading `./`, as in the output of `find .`.
+    Strip,
+
+    /// Add a leading `./` to every relative path.
+    Enforce,
+}
+
+/// Normalise a path lexically according to the enabled steps
+pub fn normalise_path(path: &str, config: &BraceConfig) -> String {
+    let sep = config.path_separator.as_str();
+    let steps = &config.normalisation;
+    if path.is_empty() || sep.is_empty() || *steps == Normalisation::default() {
+        return path.to_string();
     }
-    let first: Vec<char> = strings[0].chars().collect();
-    let mut common = vec![];
-    for (i, c) in first.iter().enumerate() {
-        if strings.iter().all(|s| s.chars().nth(i) == Some(*c)) {
-            common.push(*c);
-        } else {
-            break;
-        }
+
+    // A drive or UNC root is kept whole, so `..` never climbs out of it
+    let windows_root = match config.path_style {
+        PathStyle::Windows => windows_root(path),
+        PathStyle::Posix | PathStyle::Url => None,
+    };
+    let rest = windows_root.map_or(path, |root| &path[root.len()..]);
+
+    let mut segs = split_segments(rest, sep, &config.separators);
+    let mut trailing = segs.len() > 1 && segs.last() == Some(&"");
+    if trailing {
+        segs.pop();
     }
-    common.into_iter().collect()
-}
 
-/// Common suffix
-pub fn find_common_string_suffix(strings: &[String]) -> String {
-    if strings.is_empty() {
-        return String::new();
+    // Empty leading segments make up the root: one for `/a`, two for `//a`
+    let leading_empty = segs.iter().take_while(|s| s.is_empty()).count();
+    let root_len = if steps.collapse_separators && leading_empty > 2 {
+        1
+    } else {
+        leading_empty
+    };
+    let root = windows_root.unwrap_or_default().to_string() + &sep.repeat(root_len);
+    let body = &segs[leading_empty..];
+    let had_leading_dot = root.is_empty() && body.first() == Some(&".");
+
+    let mut out: Vec<&str> = vec![];
+    for (i, seg) in body.iter().enumerate() {
+        let is_last = i + 1 == body.len();
+        if seg.is_empty() && steps.collapse_separators {
+            continue;
+        }
+        if *seg == "." && steps.remove_dot_segments {
+            trailing |= is_last;
+            continue;
+        }
+        if *seg == ".." && steps.resolve_parent_segments {
+            if let Some(prev) = out.last() {
+                if !matches!(*prev, "" | "." | "..") {
+                    out.pop();
+                    trailing |= is_last;
+                    continue;
+                }
+            }
+        }
+        out.push(seg);
     }
-    let first: Vec<char> = strings[0].chars().collect();
-    let mut common = vec![];
-    for i in 0..first.len() {
-        let c = first[first.len() - i - 1];
-        if strings.iter().all(|s| s.chars().rev().nth(i) == Some(c)) {
-            common.push(c);
-        } else {
-            break;
+
+    if root.is_empty() {
+        match steps.leading_dot {
+            LeadingDot::Keep if had_leading_dot && out.first() != Some(&".") => out.insert(0, "."),
+            LeadingDot::Strip => {
+                while out.len() > 1 && out[0] == "." {
+                    out.remove(0);
+                }
+            }
+            // `../a` is already explicitly relative
+            LeadingDot::Enforce if !matches!(out.first(), Some(&".") | Some(&"..")) => {
+                out.insert(0, ".")
+            }
+            _ => {}
+        }
+        if out.is_empty() {
+            // Everything resolved away: the current directory
+            out.push(".");
+            trailing = false;
         }
     }
-    common.into_iter().rev().collect()
+
+    let mut result = root;
+    result.push_str(&out.join(sep));
+    if trailing && !out.is_empty() {
+        result.push_str(sep);
+    }
+    result
 }
@@ -0,0 +1,347 @@
+//! Parsing brace expressions into a tree with source spans
+
+use super::ast::BraceExpr;
+use super::escape::{find_group_end, quoted_run, EscapePolicy};
+use super::range::BraceRange;
+use crate::error::{BraceError, Result};
+use std::fmt;
+use std::ops::Range;
+
+/// A node of a parsed brace expression, with its byte span in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpannedExpr {
+    /// What the node is.
+    pub kind: SpannedKind,
+
+    /// Byte range of the node in the parsed text.
+    pub span: Range<usize>,
+}
+
+/// The kinds of node in a [`SpannedExpr`] tree
+///
+/// The root and every alternative of a group are a [`SpannedKind::Concat`],
+/// whose parts are literals, trivia, groups and sequences.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpannedKind {
+    /// Literal text, with escapes and quotes removed.
+    Literal(String),
+
+    /// An ANSI escape sequence, such as the colours written by highlighting.
+    ///
+    /// Only read when pretty-printing or highlighting, where it takes up no
+    /// width; [`parse_braces`] reads escape sequences as literal text, so that
+    /// expansion is byte-exact. Trivia expands to nothing.
+    Trivia,
+
+    /// Parts written one after another.
+    Concat(Vec<SpannedExpr>),
+
+    /// A group `{a,b}`, whose span includes the braces.
+    Alt(Vec<SpannedExpr>),
+
+    /// A sequence `{1..10}`, whose span includes the braces.
+    Range(BraceRange),
+}
+
+/// A problem found while parsing, with the byte span it covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What went wrong.
+    pub kind: ParseErrorKind,
+
+    /// Byte range of the offending text.
+    pub span: Range<usize>,
+}
+
+/// The kinds of [`ParseError`], each with the way the parser recovers from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A `{` with no matching `}`; it is read as a literal.
+    UnclosedGroup,
+
+    /// A `}` outside any group; it is read as a literal.
+    UnmatchedClose,
+
+    /// A quote with no closing quote; the rest of the input is read as quoted text.
+    UnterminatedQuote,
+
+    /// A backslash at the end of the input; it is read as a literal.
+    TrailingEscape,
+}
+
+/// The result of [`parse_braces`]: a tree for the whole input, and any errors found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedBraces {
+    /// The parsed tree, covering the whole input.
+    pub expr: SpannedExpr,
+
+    /// Errors in source order; the tree shows how each was recovered from.
+    pub errors: Vec<ParseError>,
+}
+
+/// Parse a brace expression, recovering from errors
+///
+/// Escapes and quotes are read as written under `escape`, as in
+/// [`expand_braces_with`](crate::expand_braces_with). Parsing never fails:
+/// each problem is recorded in [`ParsedBraces::errors`] and the offending
+/// text is read literally, so that all problems are reported in one pass.
+///
+/// # Example
+/// ```
+/// use braces::{parse_braces, EscapePolicy, ParseErrorKind};
+///
+/// let parsed = parse_braces("a/{b,c}}/{d", EscapePolicy::Reject);
+/// let kinds: Vec<_> = parsed.errors.iter().map(|e| (e.kind, e.span.clone())).collect();
+/// assert_eq!(
+///     kinds,
+///     vec![(ParseErrorKind::UnmatchedClose, 7..8), (ParseErrorKind::UnclosedGroup, 9..10)]
+/// );
+/// assert_eq!(parsed.expr.expand(), vec!["a/b}/{d", "a/c}/{d"]);
+/// ```
+pub fn parse_braces(text: &str, escape: EscapePolicy) -> ParsedBraces {
+    parse(text, escape, false)
+}
+
+/// Parse a brace expression that may already be highlighted, reading ANSI escapes as trivia
+pub(crate) fn parse_highlighted(text: &str, escape: EscapePolicy) -> ParsedBraces {
+    parse(text, escape, true)
+}
+
+fn parse(text: &str, escape: EscapePolicy, trivia: bool) -> ParsedBraces {
+    let (offsets, chars): (Vec<usize>, Vec<char>) = text.char_indices().unzip();
+    let mut parser = Parser {
+        chars,
+        offsets,
+        len: text.len(),
+        pos: 0,
+        escape,
+        trivia,
+        errors: vec![],
+    };
+    let expr = parser.sequence(false);
+    ParsedBraces {
+        expr,
+        errors: parser.errors,
+    }
+}
+
+impl BraceExpr {
+    /// Parse a brace expression written under `escape`
+    ///
+    /// Literals hold their text as written, so the result displays as `text`
+    /// again, except that sequences are written out as groups. Returns
+    /// [`BraceError::InvalidBraceSyntax`] with every problem found; use
+    /// [`parse_braces`] for spans and error recovery.
+    ///
+    /// # Example
+    /// ```
+    /// use braces::{BraceExpr, EscapePolicy};
+    ///
+    /// let expr = BraceExpr::parse("src/{a,b/{c,d}}.rs", EscapePolicy::Reject).unwrap();
+    /// assert_eq!(expr.expansion_count(), 3);
+    /// assert_eq!(expr.to_string(), "src/{a,b/{c,d}}.rs");
+    /// ```
+    pub fn parse(text: &str, escape: EscapePolicy) -> Result<BraceExpr> {
+        let parsed = parse_braces(text, escape);
+        if !parsed.errors.is_empty() {
+            return Err(BraceError::InvalidBraceSyntax {
+                errors: parsed.errors,
+            });
+        }
+        Ok(parsed.expr.to_expr(text))
+    }
+}
+
+impl SpannedExpr {
+    /// Expand into the strings the expression stands for, in order
+    pub fn expand(&self) -> Vec<String> {
+        match &self.kind {
+            SpannedKind::Literal(text) => vec![text.clone()],
+            SpannedKind::Trivia => vec![String::new()],
+            SpannedKind::Concat(parts) => parts.iter().fold(vec![String::new()], |acc, part| {
+                let options = part.expand();
+                acc.iter()
+                    .flat_map(|r| options.iter().map(move |opt| format!("{}{}", r, opt)))
+                    .collect()
+            }),
+            SpannedKind::Alt(options) => options.iter().flat_map(SpannedExpr::expand).collect(),
+            SpannedKind::Range(range) => range.items().collect(),
+        }
+    }
+
+    /// Convert to a [`BraceExpr`], taking literal text as written in `source`
+    pub fn to_expr(&self, source: &str) -> BraceExpr {
+        match &self.kind {
+            SpannedKind::Literal(_) => BraceExpr::Literal(source[self.span.clone()].to_string()),
+            SpannedKind::Trivia => BraceExpr::empty(),
+            SpannedKind::Concat(parts) => {
+                BraceExpr::concat(parts.iter().map(|p| p.to_expr(source)))
+            }
+            SpannedKind::Alt(options) => {
+                BraceExpr::Alt(options.iter().map(|o| o.to_expr(source)).collect())
+            }
+            SpannedKind::Range(range) => {
+                BraceExpr::Alt(range.items().map(BraceExpr::Literal).collect())
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            ParseErrorKind::UnclosedGroup => "unclosed '{'",
+            ParseErrorKind::UnmatchedClose => "unmatched '}'",
+            ParseErrorKind::UnterminatedQuote => "unterminated quote",
+            ParseErrorKind::TrailingEscape => "trailing backslash",
+        };
+        write!(f, "{} at {}..{}", what, self.span.start, self.span.end)
+    }
+}
+
+/// Recursive descent over the characters of the input
+struct Parser {
+    chars: Vec<char>,
+    offsets: Vec<usize>,
+    len: usize,
+    pos: usize,
+    escape: EscapePolicy,
+    /// Whether ANSI escape sequences are read as trivia rather than literal text
+    trivia: bool,
+    errors: Vec<ParseError>,
+}
+
+impl Parser {
+    /// Byte offset of the character at `i`, or the input length past the end
+    fn offset(&self, i: usize) -> usize {
+        self.offsets.get(i).copied().unwrap_or(self.len)
+    }
+
+    fn span(&self, start: usize, end: usize) -> Range<usize> {
+        self.offset(start)..self.offset(end)
+    }
+
+    fn error(&mut self, kind: ParseErrorKind, start: usize, end: usize) {
+        let span = self.span(start, end);
+        self.errors.push(ParseError { kind, span });
+    }
+
+    /// Parse parts up to the end of the input, or up to the `,` or `}` ending an alternative
+    fn sequence(&mut self, nested: bool) -> SpannedExpr {
+        let start = self.pos;
+        let mut parts = vec![];
+        let mut literal: Option<(usize, String)> = None;
+
+        while self.pos < self.chars.len() {
+            let at = self.pos;
+            let c = self.chars[at];
+            let text = match c {
+                ',' | '}' if nested => break,
+                '\x1b' if self.trivia && self.chars.get(at + 1) == Some(&'[') => {
+                    self.flush(&mut parts, literal.take());
+                    self.pos = self.chars[at + 2..]
+                        .iter()
+                        .position(char::is_ascii_alphabetic)
+                        .map_or(self.chars.len(), |i| at + 3 + i);
+                    parts.push(SpannedExpr {
+                        kind: SpannedKind::Trivia,
+                        span: self.span(at, self.pos),
+                    });
+                    continue;
+                }
+                '{' => match find_group_end(&self.chars, at, self.escape) {
+                    Some(end) => {
+                        self.flush(&mut parts, literal.take());
+                        let part = match self.range(end) {
+                            Some(range) => range,
+                            None => self.group(),
+                        };
+                        parts.push(part);
+                        continue;
+                    }
+                    None => {
+                        self.error(ParseErrorKind::UnclosedGroup, at, at + 1);
+                        self.pos += 1;
+                        c.to_string()
+                    }
+                },
+                '}' => {
+                    self.error(ParseErrorKind::UnmatchedClose, at, at + 1);
+                    self.pos += 1;
+                    c.to_string()
+                }
+                '\\' if self.escape.backslash_escapes() => match self.chars.get(at + 1) {
+                    Some(next) => {
+                        self.pos += 2;
+                        next.to_string()
+                    }
+                    None => {
+                        self.error(ParseErrorKind::TrailingEscape, at, at + 1);
+                        self.pos += 1;
+                        c.to_string()
+                    }
+                },
+                '\'' if self.escape.escapes() => {
+                    let (end, quoted) = quoted_run(&self.chars, at, self.escape);
+                    if end >= self.chars.len() {
+                        self.error(ParseErrorKind::UnterminatedQuote, at, end);
+                    }
+                    self.pos = (end + 1).min(self.chars.len());
+                    quoted
+                }
+                _ => {
+                    self.pos += 1;
+                    c.to_string()
+                }
+            };
+            literal.get_or_insert_with(|| (at, String::new())).1 += &text;
+        }
+
+        self.flush(&mut parts, literal);
+        SpannedExpr {
+            kind: SpannedKind::Concat(parts),
+            span: self.span(start, self.pos),
+        }
+    }
+
+    /// Parse a balanced group starting at its `{`
+    fn group(&mut self) -> SpannedExpr {
+        let start = self.pos;
+        self.pos += 1;
+        let mut options = vec![];
+        loop {
+            options.push(self.sequence(true));
+            let closing = self.chars.get(self.pos) != Some(&',');
+            self.pos += 1;
+            if closing {
+                break;
+            }
+        }
+        SpannedExpr {
+            kind: SpannedKind::Alt(options),
+            span: self.span(start, self.pos),
+        }
+    }
+
+    /// Parse a sequence starting at its `{` and ending at `end`, if the group is one
+    fn range(&mut self, end: usize) -> Option<SpannedExpr> {
+        let start = self.pos;
+        let text: String = self.chars[start + 1..end].iter().collect();
+        let range = BraceRange::parse(&text)?;
+        self.pos = end + 1;
+        Some(SpannedExpr {
+            kind: SpannedKind::Range(range),
+            span: self.span(start, self.pos),
+        })
+    }
+
+    /// End a run of literal text, adding it to `parts`
+    fn flush(&self, parts: &mut Vec<SpannedExpr>, literal: Option<(usize, String)>) {
+        if let Some((start, text)) = literal {
+            parts.push(SpannedExpr {
+                kind: SpannedKind::Literal(text),
+                span: self.span(start, self.pos),
+            });
+        }
+    }
+}
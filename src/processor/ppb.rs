@@ -1,67 +1,19 @@
 // src/processor/ppb.rs
 //! Pretty-print braces expansion syntax with indentation
 
-/// Strip ANSI escape codes from a string
-fn strip_ansi(s: &str) -> String {
-    let mut result = String::new();
-    let mut chars = s.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '\x1b' && chars.peek() == Some(&'[') {
-            chars.next(); // consume '['
-                          // Skip until we hit a letter (the command)
-            while let Some(&ch) = chars.peek() {
-                chars.next();
-                if ch.is_ascii_alphabetic() {
-                    break;
-                }
-            }
-        } else {
-            result.push(c);
-        }
-    }
-
-    result
-}
-
-/// Skip ANSI escape sequence and append to current line
-fn skip_ansi_sequence(chars: &mut std::iter::Peekable<std::str::Chars>, current_line: &mut String) {
-    current_line.push('\x1b');
-    current_line.push(chars.next().unwrap()); // '['
-    while let Some(&ch) = chars.peek() {
-        current_line.push(chars.next().unwrap());
-        if ch.is_ascii_alphabetic() {
-            break;
-        }
-    }
-}
-
-/// Flush current line to output if it has visible content
-fn flush_line_if_needed(output: &mut String, current_line: &mut String) {
-    let stripped = strip_ansi(current_line);
-    if !stripped.trim().is_empty() {
-        // Remove trailing comma if present
-        if let Some(pos) = current_line.trim_end().rfind(',') {
-            current_line.truncate(pos);
-        }
-        output.push_str(current_line);
-        output.push('\n');
-    }
-}
-
-/// Get indentation string for current stack depth
-fn get_indent(indent_stack: &[usize]) -> String {
-    " ".repeat(indent_stack.last().copied().unwrap_or(0))
-}
+use super::escape::EscapePolicy;
+use super::parse::{parse_highlighted, SpannedExpr, SpannedKind};
+use std::ops::Range;
 
 /// Pretty-print a braces expression with indentation
 ///
 /// Takes a braces expression like `"a/{b,c/{d,e},f}"` and formats it with
-/// each brace level indented for readability.
+/// each brace level indented for readability. ANSI colour codes, as written
+/// by highlighting, are kept but do not count towards the indentation.
 ///
 /// # Example
 /// ```
-/// use braces::processor::ppb::pretty_braces;
+/// use braces::pretty_braces;
 ///
 /// let expr = "a/{b,c/{d,e},f}";
 /// let pretty = pretty_braces(expr);
@@ -76,60 +28,137 @@ fn get_indent(indent_stack: &[usize]) -> String {
 /// // }
 /// ```
 pub fn pretty_braces(expr: &str) -> String {
-    let mut output = String::new();
-    let mut indent_stack: Vec<usize> = Vec::new();
-    let mut current_line = String::new();
-    let mut chars = expr.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        // Preserve but skip over ANSI color codes
-        if c == '\x1b' && chars.peek() == Some(&'[') {
-            skip_ansi_sequence(&mut chars, &mut current_line);
-            continue;
-        }
+    pretty_braces_with(expr, EscapePolicy::Reject)
+}
 
-        match c {
-            '{' => {
-                // Opening brace: output current line and increase indent
-                current_line.push('{');
-                output.push_str(&current_line);
-                output.push('\n');
+/// Pretty-print a braces expression written under an [`EscapePolicy`]
+///
+/// Escaped and quoted metacharacters stay on their line as literal text.
+pub fn pretty_braces_with(expr: &str, escape: EscapePolicy) -> String {
+    pretty_positions(expr, escape).0
+}
 
-                indent_stack.push(strip_ansi(&current_line).len());
-                current_line = get_indent(&indent_stack);
-            }
-            '}' => {
-                // Closing brace: flush pending content and decrease indent
-                flush_line_if_needed(&mut output, &mut current_line);
+/// Pretty-print, with the output offset of each byte of `expr`
+///
+/// Bytes dropped from the output, such as a trailing run of spaces, are
+/// given `usize::MAX`.
+pub(crate) fn pretty_positions(expr: &str, escape: EscapePolicy) -> (String, Vec<usize>) {
+    let parsed = parse_highlighted(expr, escape);
+    let mut printer = Printer {
+        source: expr,
+        output: String::new(),
+        indent_stack: vec![],
+        line: String::new(),
+        visible: String::new(),
+        positions: vec![usize::MAX; expr.len()],
+        pending: vec![],
+    };
+    printer.sequence(&parsed.expr);
+    printer.finish()
+}
 
-                let indent = indent_stack.pop().unwrap_or(0).saturating_sub(1);
-                output.push_str(&" ".repeat(indent));
-                output.push('}');
-                output.push('\n');
+/// Line-by-line writer for [`pretty_braces_with`]
+struct Printer<'a> {
+    source: &'a str,
+    output: String,
+    indent_stack: Vec<usize>,
+    /// The line being written, including any ANSI colour codes
+    line: String,
+    /// The visible part of `line`, without colour codes
+    visible: String,
+    /// Output offset of each source byte
+    positions: Vec<usize>,
+    /// Source bytes written to `line`, as their range and offset in the line
+    pending: Vec<(Range<usize>, usize)>,
+}
 
-                current_line = get_indent(&indent_stack);
-            }
-            ',' => {
-                // Comma: output current item and start new line at same indent
-                current_line.push(',');
-                output.push_str(&current_line);
-                output.push('\n');
-                current_line = get_indent(&indent_stack);
-            }
-            _ => {
-                current_line.push(c);
+impl Printer<'_> {
+    /// Write the parts of a sequence, opening a new level for each group
+    fn sequence(&mut self, expr: &SpannedExpr) {
+        let SpannedKind::Concat(parts) = &expr.kind else {
+            return;
+        };
+        for part in parts {
+            let text = &self.source[part.span.clone()];
+            match &part.kind {
+                SpannedKind::Trivia => {
+                    self.pending.push((part.span.clone(), self.line.len()));
+                    self.line.push_str(text);
+                }
+                SpannedKind::Alt(options) => {
+                    // Opening brace: output current line and increase indent
+                    self.pending
+                        .push((part.span.start..part.span.start + 1, self.line.len()));
+                    self.write_line("{");
+                    self.indent_stack.push(self.visible.len() + 1);
+                    self.start_line();
+
+                    for (i, option) in options.iter().enumerate() {
+                        if i > 0 {
+                            // Comma: output current item and start new line at same indent
+                            let comma = options[i - 1].span.end;
+                            self.pending.push((comma..comma + 1, self.line.len()));
+                            self.write_line(",");
+                            self.start_line();
+                        }
+                        self.sequence(option);
+                    }
+
+                    // Closing brace: flush pending content and decrease indent
+                    if !self.visible.trim().is_empty() {
+                        self.write_line("");
+                    }
+                    let indent = self.indent_stack.pop().unwrap_or(0).saturating_sub(1);
+                    self.output.push_str(&" ".repeat(indent));
+                    self.positions[part.span.end - 1] = self.output.len();
+                    self.output.push_str("}\n");
+                    self.start_line();
+                }
+                SpannedKind::Literal(_) | SpannedKind::Range(_) | SpannedKind::Concat(_) => {
+                    self.pending.push((part.span.clone(), self.line.len()));
+                    self.line.push_str(text);
+                    self.visible.push_str(text);
+                }
             }
         }
     }
 
-    // Handle any remaining content
-    if !strip_ansi(&current_line).trim().is_empty() {
-        output.push_str(&current_line);
-    } else if output.ends_with('\n') {
-        output.pop();
+    /// Output the current line, ending it with `end`
+    fn write_line(&mut self, end: &str) {
+        self.place_pending();
+        self.output.push_str(&self.line);
+        self.output.push_str(end);
+        self.output.push('\n');
+    }
+
+    /// Start a new line at the current indent
+    fn start_line(&mut self) {
+        let indent = " ".repeat(self.indent_stack.last().copied().unwrap_or(0));
+        self.pending.clear();
+        self.line = indent.clone();
+        self.visible = indent;
     }
 
-    output
+    /// Record where the source bytes in the current line land, as it is output
+    fn place_pending(&mut self) {
+        let base = self.output.len();
+        for (range, offset) in self.pending.drain(..) {
+            for (k, byte) in range.enumerate() {
+                self.positions[byte] = base + offset + k;
+            }
+        }
+    }
+
+    /// Handle any remaining content
+    fn finish(mut self) -> (String, Vec<usize>) {
+        if !self.visible.trim().is_empty() {
+            self.place_pending();
+            self.output.push_str(&self.line);
+        } else if self.output.ends_with('\n') {
+            self.output.pop();
+        }
+        (self.output, self.positions)
+    }
 }
 
 #[cfg(test)]
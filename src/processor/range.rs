@@ -0,0 +1,271 @@
+//! Sequence expressions such as `{1..10}` and `{a..e}`
+
+/// A sequence expression, `{start..end}` or `{start..end..step}`
+///
+/// As in Bash, a sequence counts up or down from `start` to `end` inclusive.
+/// The sign of the step is ignored, and a step of zero counts by one.
+/// Character sequences run between two letters of the same case.
+///
+/// # Example
+/// ```
+/// use braces::{expand_braces, BraceRange};
+///
+/// assert_eq!(expand_braces("f{08..11..2}"), vec!["f08", "f10"]);
+/// assert_eq!(expand_braces("{c..a}"), vec!["c", "b", "a"]);
+///
+/// let range = BraceRange::Numeric { start: 1, end: 10, step: 3, width: 0 };
+/// assert_eq!(range.expansion_count(), 4);
+/// assert_eq!(range.position("7"), Some(2));
+/// assert_eq!(range.position("8"), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BraceRange {
+    /// Integers, zero-padded to `width` characters (including any sign).
+    ///
+    /// `width` is the longer bound's length when either bound is written with
+    /// a leading zero, as in `{01..10}`, and zero otherwise.
+    Numeric {
+        start: i64,
+        end: i64,
+        step: u64,
+        width: usize,
+    },
+
+    /// ASCII letters.
+    Char { start: char, end: char, step: u64 },
+}
+
+impl BraceRange {
+    /// Read the text between the braces of a sequence, or `None` if it is not one
+    pub(crate) fn parse(text: &str) -> Option<BraceRange> {
+        let parts: Vec<&str> = text.split("..").collect();
+        let (start, end, step) = match parts[..] {
+            [start, end] => (start, end, 1),
+            [start, end, step] => (start, end, parse_int(step)?.unsigned_abs().max(1)),
+            _ => return None,
+        };
+
+        if let (Some(first), Some(last)) = (parse_int(start), parse_int(end)) {
+            let padded = |s: &str| {
+                s.trim_start_matches('-').len() > 1 && s.trim_start_matches('-').starts_with('0')
+            };
+            let width = if padded(start) || padded(end) {
+                start.len().max(end.len())
+            } else {
+                0
+            };
+            return Some(BraceRange::Numeric {
+                start: first,
+                end: last,
+                step,
+                width,
+            });
+        }
+
+        let letter = |s: &str| {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphabetic() => Some(c),
+                _ => None,
+            }
+        };
+        let (first, last) = (letter(start)?, letter(end)?);
+        if first.is_ascii_lowercase() != last.is_ascii_lowercase() {
+            return None;
+        }
+        Some(BraceRange::Char {
+            start: first,
+            end: last,
+            step,
+        })
+    }
+
+    /// The number of items in the sequence
+    pub fn expansion_count(&self) -> usize {
+        let (start, end, step) = self.bounds();
+        usize::try_from((end - start).unsigned_abs() / u128::from(step) + 1).unwrap_or(usize::MAX)
+    }
+
+    /// The items of the sequence, in order
+    pub fn items(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.expansion_count()).map(move |i| self.item(i))
+    }
+
+    /// The item at `index`, which must be less than [`expansion_count`](Self::expansion_count)
+    fn item(&self, index: usize) -> String {
+        let (start, end, step) = self.bounds();
+        let offset = index as i128 * i128::from(step);
+        let value = if end >= start {
+            start + offset
+        } else {
+            start - offset
+        };
+        match *self {
+            BraceRange::Numeric { width, .. } => format_padded(value, width),
+            BraceRange::Char { .. } => char::from(value as u8).to_string(),
+        }
+    }
+
+    /// The index of `text` among the items, or `None` if it is not one
+    pub fn position(&self, text: &str) -> Option<usize> {
+        let value = match *self {
+            BraceRange::Numeric { width, .. } => {
+                let value = i128::from(parse_int(text)?);
+                if format_padded(value, width) != text {
+                    return None;
+                }
+                value
+            }
+            BraceRange::Char { .. } => {
+                let mut chars = text.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c.is_ascii() => i128::from(c as u8),
+                    _ => return None,
+                }
+            }
+        };
+
+        let (start, end, step) = self.bounds();
+        let offset = if end >= start {
+            value - start
+        } else {
+            start - value
+        };
+        if offset < 0 || offset % i128::from(step) != 0 {
+            return None;
+        }
+        let index = usize::try_from(offset / i128::from(step)).ok()?;
+        (index < self.expansion_count()).then_some(index)
+    }
+
+    /// Character classes matching exactly the items, one list of classes per item shape
+    ///
+    /// Each pattern is a list of inclusive character ranges, one per character
+    /// of the items it matches: `{8..12}` gives `[8-9]` and `1[0-2]`. Returns
+    /// `None` for sequences with a step, which classes cannot describe exactly.
+    pub(crate) fn classes(&self) -> Option<Vec<Vec<(char, char)>>> {
+        match *self {
+            _ if self.bounds().2 != 1 => None,
+            BraceRange::Char { start, end, .. } => {
+                Some(vec![vec![(start.min(end), start.max(end))]])
+            }
+            BraceRange::Numeric {
+                start, end, width, ..
+            } => {
+                let (low, high) = (start.min(end), start.max(end));
+                let mut out = vec![];
+                if low < 0 {
+                    // Negative numbers are a sign before the magnitude, padded one narrower
+                    let magnitudes = high.min(-1).unsigned_abs()..=low.unsigned_abs();
+                    for pattern in digit_classes(
+                        *magnitudes.start(),
+                        *magnitudes.end(),
+                        width.saturating_sub(1),
+                    ) {
+                        out.push(std::iter::once(('-', '-')).chain(pattern).collect());
+                    }
+                }
+                if high >= 0 {
+                    out.extend(digit_classes(low.max(0) as u64, high as u64, width));
+                }
+                Some(out)
+            }
+        }
+    }
+
+    /// Start, end and step, with characters as their code points
+    fn bounds(&self) -> (i128, i128, u64) {
+        match *self {
+            BraceRange::Numeric {
+                start, end, step, ..
+            } => (start.into(), end.into(), step),
+            BraceRange::Char { start, end, step } => {
+                ((start as u32).into(), (end as u32).into(), step)
+            }
+        }
+    }
+}
+
+/// Read an integer written as an optional `-` and decimal digits
+fn parse_int(text: &str) -> Option<i64> {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Write `value` zero-padded to `width` characters, with any sign first
+fn format_padded(value: i128, width: usize) -> String {
+    if value < 0 {
+        format!("-{:0>1$}", value.unsigned_abs(), width.saturating_sub(1))
+    } else {
+        format!("{:0>1$}", value, width)
+    }
+}
+
+/// Digit classes matching the numbers from `low` to `high`, zero-padded to `width`
+fn digit_classes(low: u64, high: u64, width: usize) -> Vec<Vec<(char, char)>> {
+    let mut out = vec![];
+    let mut from = low;
+    while from <= high {
+        // Numbers with as many digits as `from`
+        let digits = from.to_string().len();
+        let to = 10u64
+            .checked_pow(digits as u32)
+            .map_or(high, |limit| high.min(limit - 1));
+        let (from_text, to_text) = (from.to_string(), to.to_string());
+        let padding = vec![('0', '0'); width.saturating_sub(digits)];
+        for pattern in same_length(from_text.as_bytes(), to_text.as_bytes()) {
+            out.push(padding.iter().copied().chain(pattern).collect());
+        }
+        match to.checked_add(1) {
+            Some(next) => from = next,
+            None => break,
+        }
+    }
+    out
+}
+
+/// Digit classes matching the numbers between two of the same length
+fn same_length(low: &[u8], high: &[u8]) -> Vec<Vec<(char, char)>> {
+    let Some((&first, low_rest)) = low.split_first() else {
+        return vec![vec![]];
+    };
+    let (&last, high_rest) = high.split_first().unwrap_or((&first, &[]));
+    let prefixed = |digit: u8, patterns: Vec<Vec<(char, char)>>| {
+        patterns.into_iter().map(move |pattern| {
+            std::iter::once((char::from(digit), char::from(digit)))
+                .chain(pattern)
+                .collect::<Vec<_>>()
+        })
+    };
+    if first == last {
+        return prefixed(first, same_length(low_rest, high_rest)).collect();
+    }
+
+    let mut out = vec![];
+    let (mut middle_first, mut middle_last) = (first, last);
+    if low_rest.iter().any(|&d| d != b'0') {
+        let nines = vec![b'9'; low_rest.len()];
+        out.extend(prefixed(first, same_length(low_rest, &nines)));
+        middle_first += 1;
+    }
+    let high_full = high_rest.iter().all(|&d| d == b'9');
+    if !high_full {
+        middle_last -= 1;
+    }
+    if middle_first <= middle_last {
+        let any = std::iter::repeat(('0', '9')).take(low_rest.len());
+        out.push(
+            std::iter::once((char::from(middle_first), char::from(middle_last)))
+                .chain(any)
+                .collect(),
+        );
+    }
+    if !high_full {
+        let zeros = vec![b'0'; high_rest.len()];
+        out.extend(prefixed(last, same_length(&zeros, high_rest)));
+    }
+    out
+}
@@ -0,0 +1,312 @@
+//! Rendering compressed path sets as regular expressions
+
+use super::ast::BraceExpr;
+use super::base::displayed_paths;
+use super::escape::{escape_text, EscapePolicy};
+use super::matcher::BraceMatcher;
+use super::range::BraceRange;
+use super::render::Renderer;
+use super::{prepare_paths, render_verified, resolve_style, BraceConfig, CaseFolding, Direction};
+use crate::error::Result;
+use std::cell::RefCell;
+
+/// Characters with a meaning in regular expressions, escaped with a backslash
+const REGEX_METACHARS: &[char] = &[
+    '\\', '.', '+', '*', '?', '(', ')', '|', '[', ']', '{', '}', '^', '$', '#', '&', '-', '~',
+];
+
+/// Compress paths into an anchored regular expression matching exactly those paths
+///
+/// The paths are compressed as by [`brace_paths_expr`](crate::brace_paths_expr), and the tree is
+/// written with [`BraceExpr::to_regex`]. A path that ends where a longer one
+/// continues, as `a/b` in `a/b/{c,}`, keeps its separator in the braces
+/// output, so the rest is made optional with the separator instead:
+/// `a/b(?:/c)?`. When `config.case_folding` folds case, the regex is made
+/// case-insensitive with `(?i)`.
+///
+/// # Example
+/// ```
+/// use braces::{brace_paths_regex, BraceConfig};
+///
+/// let paths = vec!["src/a.rs", "src/b.rs", "docs/c+d.md"];
+/// let regex = brace_paths_regex(&paths, &BraceConfig::default()).unwrap();
+/// assert_eq!(regex, r"^(?:src/(?:a\.rs|b\.rs)|docs/c\+d\.md)$");
+///
+/// let regex = brace_paths_regex(&["a/b", "a/b/c", "a/d"], &BraceConfig::default()).unwrap();
+/// assert_eq!(regex, "^a/(?:b(?:/c)?|d)$");
+/// ```
+pub fn brace_paths_regex(paths: &[impl AsRef<str>], config: &BraceConfig) -> Result<String> {
+    let config = &resolve_style(config);
+    let paths = prepare_paths(paths, config)?;
+    let expr = render_verified(&paths, config)?;
+    let expr = join_split_leaves(expr, &displayed_paths(&paths, config), config)?;
+    let regex = expr.to_regex(config.escape);
+    Ok(match config.case_folding {
+        CaseFolding::Off => regex,
+        _ => format!("(?i){}", regex),
+    })
+}
+
+/// Move the separator before a group into it where an empty alternative ends a path
+///
+/// `a/b/{c,}` is rewritten as `a/b{/c,}`. A path given with a trailing
+/// separator is written the same way, so a group is only rewritten if
+/// every such path in `shown` still matches afterwards.
+fn join_split_leaves(
+    mut expr: BraceExpr,
+    shown: &[String],
+    config: &BraceConfig,
+) -> Result<BraceExpr> {
+    let sep = escape_text(&config.path_separator, config.escape);
+    if sep.is_empty() {
+        return Ok(expr);
+    }
+    let trailing: Vec<&String> = shown
+        .iter()
+        .filter(|p| p.ends_with(config.path_separator.as_str()))
+        .collect();
+
+    // Deepest first, so that rewriting a site does not move those left to do
+    let mut sites = vec![];
+    let mut stack = vec![(&expr, vec![])];
+    while let Some((part, address)) = stack.pop() {
+        let parts = match part {
+            BraceExpr::Literal(_) => continue,
+            BraceExpr::Concat(parts) | BraceExpr::Alt(parts) => parts,
+        };
+        if matches!(part, BraceExpr::Concat(_)) {
+            for i in 0..parts.len().saturating_sub(1) {
+                if split_leaf(&parts[i], &parts[i + 1], &sep, config.direction).is_some() {
+                    sites.push((address.clone(), i));
+                }
+            }
+        }
+        for (i, child) in parts.iter().enumerate() {
+            let mut child_address = address.clone();
+            child_address.push(i);
+            stack.push((child, child_address));
+        }
+    }
+    sites.sort_by_key(|(address, _)| std::cmp::Reverse(address.len()));
+
+    for (address, i) in sites {
+        let mut candidate = expr.clone();
+        let BraceExpr::Concat(parts) = candidate.at_mut(&address) else {
+            continue;
+        };
+        let Some((first, second)) = split_leaf(&parts[i], &parts[i + 1], &sep, config.direction)
+        else {
+            continue;
+        };
+        parts[i] = first;
+        parts[i + 1] = second;
+        if !trailing.is_empty() {
+            let matcher = BraceMatcher::new(&candidate.to_string(), config)?;
+            if !trailing.iter().all(|p| matcher.is_match(p)) {
+                continue;
+            }
+        }
+        expr = candidate;
+    }
+    Ok(expr)
+}
+
+/// The two parts rewritten, if `first` and `second` end a path at a separator
+///
+/// Left to right this is a literal ending with `sep` followed by a group
+/// with an empty alternative. Right to left the group comes first and the
+/// literal starts with `sep`.
+fn split_leaf(
+    first: &BraceExpr,
+    second: &BraceExpr,
+    sep: &str,
+    direction: Direction,
+) -> Option<(BraceExpr, BraceExpr)> {
+    let (text, options) = match (direction, first, second) {
+        (Direction::LeftToRight, BraceExpr::Literal(text), BraceExpr::Alt(options)) => {
+            (text.strip_suffix(sep)?, options)
+        }
+        (Direction::RightToLeft, BraceExpr::Alt(options), BraceExpr::Literal(text)) => {
+            (text.strip_prefix(sep)?, options)
+        }
+        _ => return None,
+    };
+    let (empty, rest): (Vec<&BraceExpr>, Vec<&BraceExpr>) =
+        options.iter().partition(|option| option.is_empty());
+    if empty.is_empty() || rest.is_empty() {
+        return None;
+    }
+
+    let rest = match rest.as_slice() {
+        [only] => (*only).clone(),
+        _ => BraceExpr::Alt(rest.into_iter().cloned().collect()),
+    };
+    let text = BraceExpr::Literal(text.to_string());
+    let sep = BraceExpr::Literal(sep.to_string());
+    Some(match direction {
+        Direction::LeftToRight => (
+            text,
+            BraceExpr::Alt(vec![BraceExpr::concat([sep, rest]), BraceExpr::empty()]),
+        ),
+        Direction::RightToLeft => (
+            BraceExpr::Alt(vec![BraceExpr::concat([rest, sep]), BraceExpr::empty()]),
+            text,
+        ),
+    })
+}
+
+impl BraceExpr {
+    /// Write the expression as an anchored regular expression
+    ///
+    /// Literal text, written under `escape`, is unescaped and then escaped
+    /// for regex syntax. Groups become `(?:a|b)`, empty alternatives make
+    /// the group optional, and groups of consecutive integers become digit
+    /// classes. The regex matches exactly the strings the expression
+    /// expands to, in the syntax shared by most engines (including Rust's
+    /// `regex` crate, PCRE and JavaScript).
+    ///
+    /// # Example
+    /// ```
+    /// use braces::{BraceExpr, EscapePolicy};
+    ///
+    /// let expr = BraceExpr::parse("log/{1,2,3,4,5,6,7,8,9,10}.txt{,.gz}", EscapePolicy::Reject).unwrap();
+    /// assert_eq!(expr.to_regex(EscapePolicy::Reject), r"^log/(?:[1-9]|10)\.txt(?:\.gz)?$");
+    /// ```
+    pub fn to_regex(&self, escape: EscapePolicy) -> String {
+        format!("^{}$", self.render(&RegexRenderer::default(), escape))
+    }
+}
+
+/// Writes regex syntax, rewriting each group as it is closed
+///
+/// Groups need to be seen whole to turn runs of integers into classes and
+/// empty alternatives into `?`, so the branches of each open group are
+/// written plainly and replaced when it closes.
+#[derive(Default)]
+struct RegexRenderer {
+    /// Where each open group starts in the output, and where each of its branches starts
+    open: RefCell<Vec<(usize, Vec<usize>)>>,
+}
+
+impl Renderer for RegexRenderer {
+    fn literal(&self, out: &mut String, text: &str, _written: &str) {
+        for c in text.chars() {
+            if REGEX_METACHARS.contains(&c) {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+    }
+
+    fn group_open(&self, out: &mut String, _depth: usize) {
+        self.open.borrow_mut().push((out.len(), vec![out.len()]));
+    }
+
+    fn item_separator(&self, out: &mut String, _depth: usize) {
+        if let Some((_, branches)) = self.open.borrow_mut().last_mut() {
+            branches.push(out.len());
+        }
+    }
+
+    fn group_close(&self, out: &mut String, _depth: usize) {
+        let Some((start, starts)) = self.open.borrow_mut().pop() else {
+            return;
+        };
+        let ends = starts.iter().skip(1).copied().chain([out.len()]);
+        let (empty, options): (Vec<String>, Vec<String>) = starts
+            .iter()
+            .zip(ends)
+            .map(|(&from, to)| out[from..to].to_string())
+            .partition(String::is_empty);
+        out.truncate(start);
+        if options.is_empty() {
+            return;
+        }
+
+        let range = numeric_range(&options);
+        let branches: Vec<String> = match &range {
+            Some(patterns) => patterns.iter().map(|p| write_classes(p)).collect(),
+            None => options,
+        };
+
+        // A lone class matches one character, so needs no group even if optional
+        let bare =
+            matches!(&range, Some(patterns) if patterns.len() == 1 && patterns[0].len() == 1);
+        if bare {
+            out.push_str(&branches[0]);
+        } else {
+            out.push_str("(?:");
+            out.push_str(&branches.join("|"));
+            out.push(')');
+        }
+        if !empty.is_empty() {
+            out.push('?');
+        }
+    }
+
+    fn empty_alternative(&self, _out: &mut String, _depth: usize) {}
+}
+
+/// Digit classes for alternatives that are exactly the integers of a range
+///
+/// Only groups of at least three integers are written as classes, and only
+/// when every integer is written as the range would write it, so `{1,02,3}`
+/// is left as it is.
+fn numeric_range(texts: &[String]) -> Option<Vec<Vec<(char, char)>>> {
+    if texts.len() < 3 {
+        return None;
+    }
+    if !texts
+        .iter()
+        .all(|t| !t.is_empty() && t.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+
+    let values = texts
+        .iter()
+        .map(|t| t.parse::<i64>().ok())
+        .collect::<Option<Vec<i64>>>()?;
+    let (low, high) = (*values.iter().min()?, *values.iter().max()?);
+    let padded = texts.iter().any(|t| t.len() > 1 && t.starts_with('0'));
+    let width = if padded {
+        texts.iter().map(String::len).max().unwrap_or(0)
+    } else {
+        0
+    };
+    let range = BraceRange::Numeric {
+        start: low,
+        end: high,
+        step: 1,
+        width,
+    };
+
+    // Every item of the range appears once, written as the range writes it
+    if range.expansion_count() != texts.len() {
+        return None;
+    }
+    let mut seen = vec![false; texts.len()];
+    for text in texts {
+        let index = range.position(text)?;
+        if std::mem::replace(&mut seen[index], true) {
+            return None;
+        }
+    }
+    range.classes()
+}
+
+/// Write one character class per character, collapsing single characters
+fn write_classes(pattern: &[(char, char)]) -> String {
+    pattern
+        .iter()
+        .map(|&(low, high)| {
+            if low == high {
+                low.to_string()
+            } else if high as u32 == low as u32 + 1 {
+                format!("[{}{}]", low, high)
+            } else {
+                format!("[{}-{}]", low, high)
+            }
+        })
+        .collect()
+}
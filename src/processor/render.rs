@@ -0,0 +1,142 @@
+//! Writing expression trees in an output syntax
+
+use super::ast::BraceExpr;
+use super::escape::{unescape, EscapePolicy};
+
+/// Writes the parts of a [`BraceExpr`] in some output syntax
+///
+/// [`BraceExpr::render`] walks the tree and calls one method per part, so
+/// the same compression result can be written as shell braces, markup or
+/// anything else with groups of alternatives. Literals are passed both as
+/// their plain text and as written in braces output, with any escaping
+/// applied under the configured [`EscapePolicy`]. Groups are numbered by
+/// `depth`, 0 for the outermost, as for cycling colours per level.
+///
+/// Besides [`BraceRenderer`], highlighted output is written by
+/// `highlight::AnsiRenderer` (with the `cli` feature), and
+/// [`BraceExpr::to_regex`] writes through a renderer that rewrites each
+/// group when it closes.
+///
+/// # Example
+/// ```
+/// use braces::{brace_paths_expr, BraceConfig, EscapePolicy, Renderer};
+///
+/// /// Groups as nested HTML lists
+/// struct Html;
+///
+/// impl Renderer for Html {
+///     fn literal(&self, out: &mut String, text: &str, _written: &str) {
+///         out.push_str(&text.replace('&', "&amp;").replace('<', "&lt;"));
+///     }
+///     fn group_open(&self, out: &mut String, _depth: usize) {
+///         out.push_str("<ul><li>");
+///     }
+///     fn item_separator(&self, out: &mut String, _depth: usize) {
+///         out.push_str("</li><li>");
+///     }
+///     fn group_close(&self, out: &mut String, _depth: usize) {
+///         out.push_str("</li></ul>");
+///     }
+///     fn empty_alternative(&self, out: &mut String, _depth: usize) {
+///         out.push_str("<em>none</em>");
+///     }
+/// }
+///
+/// let config = BraceConfig {
+///     escape: EscapePolicy::Backslash,
+///     ..Default::default()
+/// };
+/// let expr = brace_paths_expr(&["a/b,c", "a/d"], &config).unwrap();
+/// assert_eq!(expr.to_string(), r"a/{b\,c,d}");
+/// assert_eq!(
+///     expr.render(&Html, config.escape),
+///     "a/<ul><li>b,c</li><li>d</li></ul>"
+/// );
+/// ```
+pub trait Renderer {
+    /// Write literal text, which braces output spells as `written`
+    fn literal(&self, out: &mut String, text: &str, written: &str);
+
+    /// Open a group
+    fn group_open(&self, out: &mut String, depth: usize);
+
+    /// Separate two alternatives of a group
+    fn item_separator(&self, out: &mut String, depth: usize);
+
+    /// Close a group
+    fn group_close(&self, out: &mut String, depth: usize);
+
+    /// Write an alternative that expands to the empty string
+    fn empty_alternative(&self, out: &mut String, depth: usize);
+}
+
+/// Brace expansion syntax, `a/{b,c}.rs`, as [`brace_paths`](crate::brace_paths) writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BraceRenderer;
+
+impl Renderer for BraceRenderer {
+    fn literal(&self, out: &mut String, _text: &str, written: &str) {
+        out.push_str(written);
+    }
+
+    fn group_open(&self, out: &mut String, _depth: usize) {
+        out.push('{');
+    }
+
+    fn item_separator(&self, out: &mut String, _depth: usize) {
+        out.push(',');
+    }
+
+    fn group_close(&self, out: &mut String, _depth: usize) {
+        out.push('}');
+    }
+
+    fn empty_alternative(&self, _out: &mut String, _depth: usize) {}
+}
+
+impl BraceExpr {
+    /// Write the expression with `renderer`
+    ///
+    /// `escape` is the policy the expression was written under, which gives
+    /// literals their plain text. With [`BraceRenderer`] this is the same as
+    /// [`to_string`](ToString::to_string).
+    pub fn render(&self, renderer: &dyn Renderer, escape: EscapePolicy) -> String {
+        let mut out = String::new();
+        self.render_into(renderer, escape, &mut out, 0);
+        out
+    }
+
+    /// Write the expression, whose groups are `depth` groups deep
+    fn render_into(
+        &self,
+        renderer: &dyn Renderer,
+        escape: EscapePolicy,
+        out: &mut String,
+        depth: usize,
+    ) {
+        match self {
+            BraceExpr::Literal(written) => {
+                renderer.literal(out, &unescape(written, escape), written)
+            }
+            BraceExpr::Concat(parts) => {
+                for part in parts {
+                    part.render_into(renderer, escape, out, depth);
+                }
+            }
+            BraceExpr::Alt(options) => {
+                renderer.group_open(out, depth);
+                for (i, option) in options.iter().enumerate() {
+                    if i > 0 {
+                        renderer.item_separator(out, depth);
+                    }
+                    if option.is_empty() {
+                        renderer.empty_alternative(out, depth);
+                    } else {
+                        option.render_into(renderer, escape, out, depth + 1);
+                    }
+                }
+                renderer.group_close(out, depth);
+            }
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! Splitting paths into the segments that form the levels of the trie
+
+use super::normalise::split_segments;
+use super::style::PathStyle;
+use super::url::url_segments;
+use super::windows::windows_root;
+use super::BraceConfig;
+
+/// Splits paths into the segments that become levels of the trie
+///
+/// Segments are returned in reading order, and are reversed for
+/// right-to-left grouping. Joining them with [`separator`](Self::separator)
+/// must give back the path, as the output is written by joining them so.
+/// Pass an implementation to [`brace_paths_with`](crate::brace_paths_with)
+/// to compress things other than file paths.
+///
+/// # Example
+/// ```
+/// use braces::{brace_paths_with, BraceConfig, Segmenter};
+///
+/// /// Kafka topics, split at dots
+/// struct Topics;
+///
+/// impl Segmenter for Topics {
+///     fn segments<'p>(&self, path: &'p str) -> Vec<&'p str> {
+///         path.split('.').collect()
+///     }
+///
+///     fn separator(&self) -> &str {
+///         "."
+///     }
+/// }
+///
+/// let topics = vec!["orders.eu.v1", "orders.eu.v2", "orders.us.v3"];
+/// let result = brace_paths_with(&topics, &BraceConfig::default(), &Topics).unwrap();
+/// assert_eq!(result, "orders.{eu.{v1,v2},us.v3}");
+/// ```
+pub trait Segmenter {
+    /// Split `path` into segments
+    fn segments<'p>(&self, path: &'p str) -> Vec<&'p str>;
+
+    /// The text written between segments
+    fn separator(&self) -> &str;
+}
+
+/// Split at every occurrence of one separator
+///
+/// # Example
+/// With `SeparatorSegmenter::new(".")`:
+/// - `["http.server.requests.count", "http.server.requests.sum"]` → `"http.server.requests.{count,sum}"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeparatorSegmenter {
+    separator: String,
+}
+
+impl SeparatorSegmenter {
+    /// Split at `separator`, which is also written between segments
+    pub fn new(separator: impl Into<String>) -> Self {
+        SeparatorSegmenter {
+            separator: separator.into(),
+        }
+    }
+}
+
+impl Segmenter for SeparatorSegmenter {
+    fn segments<'p>(&self, path: &'p str) -> Vec<&'p str> {
+        if self.separator.is_empty() {
+            return vec![path];
+        }
+        path.split(self.separator.as_str()).collect()
+    }
+
+    fn separator(&self) -> &str {
+        &self.separator
+    }
+}
+
+/// Split after any of several delimiters, each kept at the end of its segment
+///
+/// The longest delimiter matching at each position is taken, and segments
+/// are joined directly.
+///
+/// # Example
+/// With `MultiSeparatorSegmenter::new([".", "$"])`:
+/// - `["com.example.Outer$A", "com.example.Outer$B"]` → `"com.example.Outer${A,B}"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiSeparatorSegmenter {
+    delimiters: Vec<String>,
+}
+
+impl MultiSeparatorSegmenter {
+    /// Split after each of `delimiters` (empty ones are ignored)
+    pub fn new(delimiters: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let mut delimiters: Vec<String> = delimiters
+            .into_iter()
+            .map(Into::into)
+            .filter(|d: &String| !d.is_empty())
+            .collect();
+        // Longest first, so the first match at a position is the longest
+        delimiters.sort_by_key(|d| std::cmp::Reverse(d.len()));
+        MultiSeparatorSegmenter { delimiters }
+    }
+}
+
+impl Segmenter for MultiSeparatorSegmenter {
+    fn segments<'p>(&self, path: &'p str) -> Vec<&'p str> {
+        let mut segments = vec![];
+        let (mut start, mut at) = (0, 0);
+        while at < path.len() {
+            let rest = &path[at..];
+            match self
+                .delimiters
+                .iter()
+                .find(|d| rest.starts_with(d.as_str()))
+            {
+                Some(delimiter) => {
+                    at += delimiter.len();
+                    if at < path.len() {
+                        segments.push(&path[start..at]);
+                        start = at;
+                    }
+                }
+                None => at += rest.chars().next().map_or(1, char::len_utf8),
+            }
+        }
+        segments.push(&path[start..]);
+        segments
+    }
+
+    fn separator(&self) -> &str {
+        ""
+    }
+}
+
+/// Split into words at token boundaries, without regular expressions
+///
+/// A token ends after a run of punctuation, before an uppercase letter that
+/// follows a lowercase letter or digit (`fooBar`), and before the last
+/// capital of an acronym followed by a lowercase letter (`HTTPServer`).
+/// Punctuation stays at the end of the token before it, digits stay with
+/// their word, and a character keeps its combining marks. Segments are
+/// joined directly. With the `graphemes` feature, text is split into whole
+/// grapheme clusters first; without it, a built-in table of combining marks
+/// is used, which may split clusters in some scripts.
+///
+/// # Example
+/// With `TokenSegmenter`:
+/// - `["XMLHttpRequest", "XMLHttpResponse"]` → `"XMLHttp{Request,Response}"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TokenSegmenter;
+
+/// Kinds of character that token boundaries fall between
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharKind {
+    Upper,
+    Lower,
+    Digit,
+    Other,
+}
+
+impl CharKind {
+    /// The kind of a grapheme cluster, from its first character
+    fn of(cluster: &str) -> CharKind {
+        match cluster.chars().next() {
+            Some(c) if c.is_uppercase() => CharKind::Upper,
+            Some(c) if c.is_numeric() => CharKind::Digit,
+            // Letters without case, as in CJK, join words like lowercase ones
+            Some(c) if c.is_alphanumeric() => CharKind::Lower,
+            _ => CharKind::Other,
+        }
+    }
+}
+
+impl Segmenter for TokenSegmenter {
+    fn segments<'p>(&self, path: &'p str) -> Vec<&'p str> {
+        let clusters = grapheme_clusters(path);
+        let kinds: Vec<CharKind> = clusters.iter().map(|c| CharKind::of(c)).collect();
+
+        let mut segments = vec![];
+        let mut start = 0;
+        let mut at = 0;
+        for (i, cluster) in clusters.iter().enumerate() {
+            if i > 0 {
+                let (prev, cur) = (kinds[i - 1], kinds[i]);
+                let next = kinds.get(i + 1).copied();
+                let boundary = match (prev, cur) {
+                    (CharKind::Other, CharKind::Other) => false,
+                    (CharKind::Other, _) => true,
+                    (CharKind::Lower | CharKind::Digit, CharKind::Upper) => true,
+                    (CharKind::Upper, CharKind::Upper) => next == Some(CharKind::Lower),
+                    _ => false,
+                };
+                if boundary {
+                    segments.push(&path[start..at]);
+                    start = at;
+                }
+            }
+            at += cluster.len();
+        }
+        segments.push(&path[start..]);
+        segments
+    }
+
+    fn separator(&self) -> &str {
+        ""
+    }
+}
+
+/// Split into user-perceived characters, joined directly
+///
+/// Text is split into Unicode extended grapheme clusters, so no cluster is
+/// ever cut, whether a letter with combining marks, an emoji sequence, a
+/// flag or a Hangul syllable. Only available with the `graphemes` feature.
+///
+/// # Example
+/// With `GraphemeSegmenter` and `allow_stem_split`:
+/// - `["cafe\u{301}s", "cafe\u{301}"]` → `"cafe\u{301}{s,}"`
+#[cfg(feature = "graphemes")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GraphemeSegmenter;
+
+#[cfg(feature = "graphemes")]
+impl Segmenter for GraphemeSegmenter {
+    fn segments<'p>(&self, path: &'p str) -> Vec<&'p str> {
+        grapheme_clusters(path)
+    }
+
+    fn separator(&self) -> &str {
+        ""
+    }
+}
+
+/// Split text into grapheme clusters, as described on [`GraphemeSegmenter`]
+#[cfg(feature = "graphemes")]
+fn grapheme_clusters(text: &str) -> Vec<&str> {
+    use unicode_segmentation::UnicodeSegmentation;
+    if text.is_empty() {
+        return vec![text];
+    }
+    text.graphemes(true).collect()
+}
+
+/// Zero-width joiner, which joins the characters either side of it
+#[cfg(not(feature = "graphemes"))]
+const ZWJ: char = '\u{200D}';
+
+/// Ranges of characters that extend the cluster before them
+#[cfg(not(feature = "graphemes"))]
+const EXTENDING: &[(char, char)] = &[
+    ('\u{0300}', '\u{036F}'),
+    ('\u{0483}', '\u{0489}'),
+    ('\u{0591}', '\u{05BD}'),
+    ('\u{05BF}', '\u{05C7}'),
+    ('\u{0610}', '\u{061A}'),
+    ('\u{064B}', '\u{065F}'),
+    ('\u{0670}', '\u{0670}'),
+    ('\u{06D6}', '\u{06ED}'),
+    ('\u{0900}', '\u{0903}'),
+    ('\u{093A}', '\u{094F}'),
+    ('\u{0951}', '\u{0957}'),
+    ('\u{0962}', '\u{0963}'),
+    ('\u{0981}', '\u{0983}'),
+    ('\u{09BC}', '\u{09D7}'),
+    ('\u{0E31}', '\u{0E31}'),
+    ('\u{0E34}', '\u{0E3A}'),
+    ('\u{0E47}', '\u{0E4E}'),
+    ('\u{1160}', '\u{11FF}'),
+    ('\u{1AB0}', '\u{1AFF}'),
+    ('\u{1DC0}', '\u{1DFF}'),
+    ('\u{200C}', '\u{200D}'),
+    ('\u{20D0}', '\u{20FF}'),
+    ('\u{3099}', '\u{309A}'),
+    ('\u{FE00}', '\u{FE0F}'),
+    ('\u{FE20}', '\u{FE2F}'),
+    ('\u{1F3FB}', '\u{1F3FF}'),
+    ('\u{E0020}', '\u{E007F}'),
+    ('\u{E0100}', '\u{E01EF}'),
+];
+
+/// Whether `c` extends the cluster before it
+#[cfg(not(feature = "graphemes"))]
+fn extends(c: char) -> bool {
+    EXTENDING
+        .iter()
+        .any(|&(low, high)| (low..=high).contains(&c))
+}
+
+/// Whether `c` is a regional indicator, two of which make a flag
+#[cfg(not(feature = "graphemes"))]
+fn is_regional(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Split text into characters with their combining marks, approximating grapheme clusters
+///
+/// A base character keeps its combining marks, variation selectors and
+/// emoji modifiers, characters joined by a zero-width joiner stay together,
+/// flags are not split, and `\r\n` is one character. The marks covered are
+/// those of Latin, Greek, Cyrillic, Hebrew, Arabic, Devanagari, Bengali and
+/// Thai, so clusters in other scripts (such as Tamil vowel signs) may be split.
+#[cfg(not(feature = "graphemes"))]
+fn grapheme_clusters(text: &str) -> Vec<&str> {
+    let mut clusters = vec![];
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    // Regional indicators in the current cluster, paired up into flags
+    let mut regional = 0;
+    for (at, c) in text.char_indices() {
+        if let Some(p) = prev {
+            let joined = (p == '\r' && c == '\n')
+                || extends(c)
+                || p == ZWJ
+                || (is_regional(p) && is_regional(c) && regional % 2 == 1);
+            if !joined {
+                clusters.push(&text[start..at]);
+                start = at;
+                regional = 0;
+            }
+        }
+        if is_regional(c) {
+            regional += 1;
+        }
+        prev = Some(c);
+    }
+    clusters.push(&text[start..]);
+    clusters
+}
+
+/// The segmenter for file paths, as set up by the configuration
+///
+/// Splits at `sep` (recognising the other configured separators, so that a
+/// longer one is not cut), keeps a Windows drive or UNC root as one segment,
+/// and splits URLs into their parts.
+pub(crate) struct PathSegmenter<'c> {
+    sep: &'c str,
+    config: &'c BraceConfig,
+}
+
+impl<'c> PathSegmenter<'c> {
+    pub(crate) fn new(sep: &'c str, config: &'c BraceConfig) -> Self {
+        PathSegmenter { sep, config }
+    }
+}
+
+impl Segmenter for PathSegmenter<'_> {
+    fn segments<'p>(&self, path: &'p str) -> Vec<&'p str> {
+        let (sep, config) = (self.sep, self.config);
+        if config.allow_segment_split && config.path_style == PathStyle::Url {
+            return url_segments(path);
+        }
+        if sep.is_empty() {
+            return vec![path];
+        }
+        match root_segment(path, sep, config).filter(|_| config.allow_segment_split) {
+            // A drive or UNC root is one segment, even though it contains separators
+            Some((root, rest)) => std::iter::once(root)
+                .chain(
+                    rest.map(|r| split_segments(r, sep, &config.separators))
+                        .unwrap_or_default(),
+                )
+                .collect(),
+            None => split_segments(path, sep, &config.separators),
+        }
+    }
+
+    fn separator(&self) -> &str {
+        self.sep
+    }
+}
+
+/// Split a Windows root from the rest of the path (`None` for the rest if nothing follows)
+fn root_segment<'p>(
+    path: &'p str,
+    sep: &str,
+    config: &BraceConfig,
+) -> Option<(&'p str, Option<&'p str>)> {
+    if config.path_style != PathStyle::Windows {
+        return None;
+    }
+    let root = windows_root(path)?;
+    Some((root, path[root.len()..].strip_prefix(sep)))
+}
@@ -0,0 +1,118 @@
+//! Compression of pre-segmented sequences of tokens
+
+use super::escape::REJECTED;
+use super::normalise::find_common_suffix;
+use super::trie::build_trie_from_segments;
+use super::verify::verify_output;
+use super::{finish_output, render_trie_expr, BraceConfig, Direction, EscapePolicy};
+use crate::error::{BraceError, Result};
+use std::collections::HashSet;
+use std::fmt::Display;
+
+/// Compress pre-segmented sequences, joining tokens with `joiner` in the output
+///
+/// Each sequence is a path already split into its segments, such as the
+/// components of a git tree walk or a JSON pointer. Tokens are never split,
+/// so they may contain `/` or the joiner itself (although the output then
+/// no longer identifies the token boundaries). Tokens are written with their
+/// [`Display`] form. With `sort_items`, sequences are ordered by `T`'s [`Ord`],
+/// so numbers sort numerically.
+///
+/// Options that split or rewrite paths as text (`path_separator`, `separators`,
+/// `path_style`, `normalisation`, `base` and `reprocess_braces`) do not apply.
+///
+/// # Example
+/// ```
+/// use braces::{brace_sequences, BraceConfig};
+///
+/// let sequences = vec![
+///     vec!["refs", "heads/main"],
+///     vec!["refs", "heads/dev"],
+///     vec!["refs", "tags/v1"],
+/// ];
+/// let result = brace_sequences(&sequences, "/", &BraceConfig::default()).unwrap();
+/// assert_eq!(result, "refs/{heads/main,heads/dev,tags/v1}");
+/// ```
+pub fn brace_sequences<T: Ord + Display>(
+    sequences: &[impl AsRef<[T]>],
+    joiner: &str,
+    config: &BraceConfig,
+) -> Result<String> {
+    if sequences.is_empty() {
+        return Err(BraceError::EmptyInput);
+    }
+
+    let mut ordered: Vec<&[T]> = sequences.iter().map(AsRef::as_ref).collect();
+    if config.sort_items {
+        ordered.sort();
+    }
+
+    let mut seen = HashSet::new();
+    let mut tokens: Vec<Vec<String>> = vec![];
+    for sequence in ordered {
+        let mut seq: Vec<String> = sequence.iter().map(ToString::to_string).collect();
+        if seq.is_empty() {
+            seq.push(String::new());
+        }
+        let key: Vec<String> = seq.iter().map(|t| config.case_folding.fold(t)).collect();
+        if !config.deduplicate_inputs || seen.insert(key) {
+            tokens.push(seq);
+        }
+    }
+
+    let joined: Vec<String> = tokens.iter().map(|seq| seq.join(joiner)).collect();
+    if config.escape == EscapePolicy::Reject {
+        if let Some(path) = joined.iter().find(|p| p.contains(REJECTED)) {
+            return Err(BraceError::InvalidBraceInput {
+                path: path.clone(),
+                reason: "contains brace metacharacters and no escape policy is set".to_string(),
+            });
+        }
+    }
+
+    // Tokens are already in order, and joined with the joiner when rendering
+    let config = &BraceConfig {
+        path_separator: joiner.to_string(),
+        sort_items: false,
+        ..config.clone()
+    };
+    let common_suffix = strip_last_token_suffix(&mut tokens, config);
+    for seq in tokens.iter_mut() {
+        config.direction.order(seq);
+    }
+
+    let (nodes, root_idx) = build_trie_from_segments(&tokens, config);
+    let expr = render_trie_expr(
+        &nodes,
+        root_idx,
+        &common_suffix,
+        &config.path_separator,
+        config,
+    );
+    let expr = verify_output(expr, &joined, config)?;
+    Ok(finish_output(&expr, config))
+}
+
+/// Strip the suffix shared by every final token, like a shared file extension
+///
+/// Nothing is stripped right to left or in `stable_output` mode, or if a
+/// token would be left empty.
+fn strip_last_token_suffix(tokens: &mut [Vec<String>], config: &BraceConfig) -> String {
+    if config.stable_output || config.direction == Direction::RightToLeft {
+        return String::new();
+    }
+    let last: Vec<String> = tokens
+        .iter()
+        .filter_map(|seq| seq.last().cloned())
+        .collect();
+    let suffix = find_common_suffix(&last);
+    if suffix.is_empty() || last.iter().any(|t| t.len() == suffix.len()) {
+        return String::new();
+    }
+    for seq in tokens.iter_mut() {
+        if let Some(t) = seq.last_mut() {
+            t.truncate(t.len() - suffix.len());
+        }
+    }
+    suffix
+}
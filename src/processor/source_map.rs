@@ -0,0 +1,270 @@
+//! Mapping spans of braces output back to the input paths they cover
+
+use super::ast::BraceExpr;
+use super::base::displayed_paths;
+use super::escape::{unescape, EscapePolicy};
+use super::ppb::pretty_positions;
+use super::render::Renderer;
+use super::{output_renderer, prepare_paths, render_verified, resolve_style, BraceConfig};
+use crate::error::Result;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Braces output with a source map from its spans to the input paths
+///
+/// Each literal and group in `text` has a [`SourceSpan`] listing the input
+/// paths it contributes to, by their index in the input. A path given more
+/// than once is listed under each of its indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedBraces {
+    /// The braces expression, highlighted if configured.
+    pub text: String,
+
+    /// Spans of `text`, outer before inner and otherwise in order.
+    pub spans: Vec<SourceSpan>,
+}
+
+/// A span of braces output and the input paths it covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceSpan {
+    /// Byte range in the output, including the braces of a group.
+    pub span: Range<usize>,
+
+    /// Whether the span is literal text or a group.
+    pub kind: SpanKind,
+
+    /// Indices of the input paths the span contributes to, in order.
+    pub inputs: Vec<usize>,
+}
+
+/// The kinds of [`SourceSpan`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    /// A run of literal text.
+    Literal,
+
+    /// A group `{a,b}`.
+    Group,
+}
+
+/// Expand paths into braces, with a source map back to the input
+///
+/// The text is the same as [`brace_paths`](crate::brace_paths) writes,
+/// highlighted when `config.highlight` is set, and the spans are byte ranges
+/// of that text. Use [`MappedBraces::pretty`] to pretty-print it with the
+/// spans kept.
+///
+/// # Example
+/// ```
+/// use braces::{brace_paths_mapped, BraceConfig, SpanKind};
+///
+/// let paths = vec!["src/a.rs", "src/b.rs", "README.md"];
+/// let mapped = brace_paths_mapped(&paths, &BraceConfig::default()).unwrap();
+/// assert_eq!(mapped.text, "{src/{a.rs,b.rs},README.md}");
+///
+/// let group = mapped.span_at(5).unwrap();
+/// assert_eq!((group.span.clone(), group.kind), (5..16, SpanKind::Group));
+/// assert_eq!(group.inputs, vec![0, 1]);
+/// assert_eq!(mapped.span_at(20).unwrap().inputs, vec![2]);
+/// ```
+pub fn brace_paths_mapped(paths: &[impl AsRef<str>], config: &BraceConfig) -> Result<MappedBraces> {
+    let config = &resolve_style(config);
+    let prepared = prepare_paths(paths, config)?;
+    let expr = render_verified(&prepared, config)?;
+
+    let recorder = SpanRecorder::new(output_renderer(config));
+    let text = expr.render(&recorder, config.escape);
+    let nodes = recorder.spans.into_inner();
+    let expanded = expansions(&expr, config.escape, &mut 0);
+
+    let mut inputs: Vec<Vec<usize>> = vec![vec![]; nodes.len()];
+    let keys = input_keys(paths, config)?;
+    for (path, ids) in expanded {
+        let Some(indices) = lookup(&keys, &path, config) else {
+            continue;
+        };
+        for id in ids {
+            inputs[id].extend(indices);
+        }
+    }
+
+    let spans = nodes
+        .into_iter()
+        .zip(inputs)
+        .map(|((span, kind), mut inputs)| {
+            inputs.sort_unstable();
+            inputs.dedup();
+            SourceSpan { span, kind, inputs }
+        })
+        .collect();
+    Ok(MappedBraces { text, spans })
+}
+
+impl MappedBraces {
+    /// The innermost span containing the byte at `at`
+    pub fn span_at(&self, at: usize) -> Option<&SourceSpan> {
+        self.spans.iter().rev().find(|s| s.span.contains(&at))
+    }
+
+    /// Pretty-print the text as [`pretty_braces_with`](crate::pretty_braces_with) does, keeping the spans
+    pub fn pretty(&self, escape: EscapePolicy) -> MappedBraces {
+        let (text, positions) = pretty_positions(&self.text, escape);
+        self.moved(text, &positions)
+    }
+
+    /// The same spans in `text`, where each byte of the old text was written at `positions`
+    fn moved(&self, text: String, positions: &[usize]) -> MappedBraces {
+        let spans = self
+            .spans
+            .iter()
+            .map(|s| {
+                let mut written = positions[s.span.clone()]
+                    .iter()
+                    .copied()
+                    .filter(|&p| p != usize::MAX);
+                let span = match written.next() {
+                    Some(start) => start..written.next_back().unwrap_or(start) + 1,
+                    None => 0..0,
+                };
+                SourceSpan { span, ..s.clone() }
+            })
+            .collect();
+        MappedBraces { text, spans }
+    }
+}
+
+/// Writes output with another renderer, recording the span of each literal and group
+///
+/// Spans are numbered in the order they are opened, as by [`expansions`].
+/// A group's span covers everything its renderer writes for it, such as
+/// the colour codes around its braces.
+struct SpanRecorder<'a> {
+    inner: &'a dyn Renderer,
+    spans: RefCell<Vec<(Range<usize>, SpanKind)>>,
+    /// Groups not yet closed, by their index in `spans`
+    open: RefCell<Vec<usize>>,
+}
+
+impl<'a> SpanRecorder<'a> {
+    fn new(inner: &'a dyn Renderer) -> Self {
+        SpanRecorder {
+            inner,
+            spans: RefCell::default(),
+            open: RefCell::default(),
+        }
+    }
+}
+
+impl Renderer for SpanRecorder<'_> {
+    fn literal(&self, out: &mut String, text: &str, written: &str) {
+        if written.is_empty() {
+            return;
+        }
+        let start = out.len();
+        self.inner.literal(out, text, written);
+        self.spans
+            .borrow_mut()
+            .push((start..out.len(), SpanKind::Literal));
+    }
+
+    fn group_open(&self, out: &mut String, depth: usize) {
+        let mut spans = self.spans.borrow_mut();
+        self.open.borrow_mut().push(spans.len());
+        spans.push((out.len()..out.len(), SpanKind::Group));
+        self.inner.group_open(out, depth);
+    }
+
+    fn item_separator(&self, out: &mut String, depth: usize) {
+        self.inner.item_separator(out, depth);
+    }
+
+    fn group_close(&self, out: &mut String, depth: usize) {
+        self.inner.group_close(out, depth);
+        if let Some(id) = self.open.borrow_mut().pop() {
+            self.spans.borrow_mut()[id].0.end = out.len();
+        }
+    }
+
+    fn empty_alternative(&self, out: &mut String, depth: usize) {
+        self.inner.empty_alternative(out, depth);
+    }
+}
+
+/// The paths `expr` expands to, each with the literals and groups it passes through
+///
+/// Literals and groups are numbered from `next` in the order
+/// [`SpanRecorder`] records them.
+fn expansions(
+    expr: &BraceExpr,
+    escape: EscapePolicy,
+    next: &mut usize,
+) -> Vec<(String, Vec<usize>)> {
+    match expr {
+        BraceExpr::Literal(written) if written.is_empty() => vec![(String::new(), vec![])],
+        BraceExpr::Literal(written) => {
+            *next += 1;
+            vec![(unescape(written, escape), vec![*next - 1])]
+        }
+        BraceExpr::Concat(parts) => {
+            parts
+                .iter()
+                .fold(vec![(String::new(), vec![])], |acc, part| {
+                    let options = expansions(part, escape, next);
+                    acc.iter()
+                        .flat_map(|(path, ids)| {
+                            options.iter().map(move |(more, more_ids)| {
+                                let ids = ids.iter().chain(more_ids).copied().collect();
+                                (format!("{}{}", path, more), ids)
+                            })
+                        })
+                        .collect()
+                })
+        }
+        BraceExpr::Alt(options) => {
+            let id = *next;
+            *next += 1;
+            let mut out = vec![];
+            for option in options {
+                for (path, mut ids) in expansions(option, escape, next) {
+                    ids.push(id);
+                    out.push((path, ids));
+                }
+            }
+            out
+        }
+    }
+}
+
+/// The indices of the inputs by the path they are written as in the output
+fn input_keys(
+    paths: &[impl AsRef<str>],
+    config: &BraceConfig,
+) -> Result<HashMap<String, Vec<usize>>> {
+    let mut keys: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, path) in paths.iter().enumerate() {
+        let prepared = prepare_paths(&[path.as_ref()], config)?;
+        for shown in displayed_paths(&prepared, config) {
+            keys.entry(config.case_folding.fold(&shown))
+                .or_default()
+                .push(i);
+        }
+    }
+    Ok(keys)
+}
+
+/// The inputs written as `path`
+///
+/// A path split from a longer one, as `a/b` in `a/b/{c,}`, expands with a
+/// trailing separator, so is also looked up without it.
+fn lookup<'a>(
+    keys: &'a HashMap<String, Vec<usize>>,
+    path: &str,
+    config: &BraceConfig,
+) -> Option<&'a Vec<usize>> {
+    let sep = config.path_separator.as_str();
+    keys.get(&config.case_folding.fold(path)).or_else(|| {
+        let trimmed = path.strip_suffix(sep).filter(|_| !sep.is_empty())?;
+        keys.get(&config.case_folding.fold(trimmed))
+    })
+}
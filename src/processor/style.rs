@@ -0,0 +1,66 @@
+//! Path styles, which adjust the configuration to the conventions of the input
+
+use super::escape::EscapePolicy;
+use super::{BraceConfig, CaseFolding};
+use std::borrow::Cow;
+
+/// Conventions of the paths being compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathStyle {
+    /// Separators, escaping and case are as configured (default).
+    #[default]
+    Posix,
+
+    /// Windows paths such as `C:\Users\x\a.rs` and `\\server\share\b.txt`.
+    ///
+    /// The separator is `\` (with `/` also recognised), a drive or UNC prefix
+    /// is kept together as one root segment, and segments are compared
+    /// case-insensitively (`case_folding` defaults to [`CaseFolding::FirstSeen`]).
+    /// As `\` cannot also be an escape character, any escaping policy other
+    /// than [`EscapePolicy::Reject`] becomes [`EscapePolicy::QuoteDoubled`].
+    Windows,
+
+    /// URLs such as `https://api.example.com/v1/users?page=2`.
+    ///
+    /// The scheme and authority form the root segment, each path segment is a
+    /// level, and the query parameters and fragment are further levels below
+    /// the path. Segments keep their leading `/`, `?`, `&` or `#`, and a
+    /// delimiter shared by a whole group is written once in front of it:
+    /// `https://example.com/v1/{users,groups}?page=2`.
+    Url,
+}
+
+impl BraceConfig {
+    /// The configuration as used for processing, with the conventions of `path_style` applied
+    ///
+    /// Under [`PathStyle::Windows`] this is where the separator, case folding
+    /// and escaping policy actually in effect can be read, as for expanding
+    /// or pretty-printing the output with the matching escaping policy.
+    pub fn resolved(&self) -> BraceConfig {
+        resolve_style(self).into_owned()
+    }
+}
+
+/// The configuration with the conventions of `path_style` applied
+pub(crate) fn resolve_style(config: &BraceConfig) -> Cow<'_, BraceConfig> {
+    let mut resolved = config.clone();
+    match config.path_style {
+        PathStyle::Posix => return Cow::Borrowed(config),
+        PathStyle::Windows => {
+            resolved.path_separator = "\\".to_string();
+            resolved.separators = vec!["\\".to_string(), "/".to_string()];
+            if resolved.case_folding == CaseFolding::Off {
+                resolved.case_folding = CaseFolding::FirstSeen;
+            }
+            if resolved.escape.escapes() {
+                resolved.escape = EscapePolicy::QuoteDoubled;
+            }
+        }
+        PathStyle::Url => {
+            // Segments carry their own delimiters, so they are joined directly
+            resolved.path_separator = String::new();
+            resolved.separators = vec![];
+        }
+    }
+    Cow::Owned(resolved)
+}
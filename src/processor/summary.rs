@@ -0,0 +1,238 @@
+//! Zoomed-out overviews of large path sets with per-subtree counts
+
+use super::ast::BraceExpr;
+use super::base::{base_prefix, relative_to};
+use super::escape::escape_text;
+use super::expansion::compose_label_and_items;
+use super::trie::{build_trie, Node};
+use super::{prepare_paths, resolve_style, BraceConfig};
+use crate::error::{BraceError, Result};
+use std::collections::HashSet;
+
+/// Summarise paths down to `depth` levels, annotating each group with its leaf count
+///
+/// Groups below the zoom depth are replaced by their name and the number of
+/// paths they contain, so `depth = 1` gives a top-level overview such as
+/// `{src(412),tests(88),docs(12)}`, and `depth = 0` only counts the whole set
+/// as `(512)`. Paths that end above the cutoff are listed as normal. As in
+/// [`brace_paths`](crate::brace_paths), a shared root such as `/` is kept in
+/// front and paths are shown relative to `base` if one is set. The output is
+/// a summary and cannot be expanded back.
+///
+/// # Example
+/// ```
+/// use braces::{summarise_paths, BraceConfig};
+///
+/// let paths = vec!["src/a.rs", "src/b/c.rs", "src/b/d.rs", "tests/e.rs"];
+/// let config = BraceConfig::default();
+/// assert_eq!(summarise_paths(&paths, &config, 1).unwrap(), "{src(3),tests(1)}");
+/// assert_eq!(summarise_paths(&paths, &config, 2).unwrap(), "{src/{a.rs,b(2)},tests/e.rs}");
+/// ```
+pub fn summarise_paths(
+    paths: &[impl AsRef<str>],
+    config: &BraceConfig,
+    depth: usize,
+) -> Result<String> {
+    let config = &resolve_style(config);
+    let paths = prepare_paths(paths, config)?;
+    Ok(summarise(&paths, None, config, depth))
+}
+
+/// Summarise paths with sizes, annotating each group with its leaf count and byte total
+///
+/// As [`summarise_paths`], but each group is shown as `src(412; 1.2 MiB)`.
+/// When inputs are deduplicated, the size of the first occurrence is used.
+///
+/// # Example
+/// ```
+/// use braces::{summarise_sized_paths, BraceConfig};
+///
+/// let paths = vec![("src/a.rs", 1024), ("src/b.rs", 2048), ("README.md", 10)];
+/// let result = summarise_sized_paths(&paths, &BraceConfig::default(), 1).unwrap();
+/// assert_eq!(result, "{src(2; 3.0 KiB),README.md}");
+/// ```
+pub fn summarise_sized_paths(
+    paths: &[(impl AsRef<str>, u64)],
+    config: &BraceConfig,
+    depth: usize,
+) -> Result<String> {
+    if paths.is_empty() {
+        return Err(BraceError::EmptyInput);
+    }
+
+    let config = &resolve_style(config);
+    let mut seen = HashSet::new();
+    let mut prepared = vec![];
+    let mut sizes = vec![];
+    for (path, size) in paths {
+        for p in prepare_paths(&[path.as_ref()], config)? {
+            if !config.deduplicate_inputs || seen.insert(config.case_folding.fold(&p)) {
+                prepared.push(p);
+                sizes.push(*size);
+            }
+        }
+    }
+
+    Ok(summarise(&prepared, Some(&sizes), config, depth))
+}
+
+/// Leaf count and optional byte total of a subtree
+struct Tally {
+    count: usize,
+    bytes: Option<u64>,
+}
+
+/// Render the paths at the requested zoom depth, as `render_paths` groups them
+///
+/// With a `base` directory, paths under it are summarised relative to it and
+/// any kept outside it follow in a separate group.
+fn summarise(
+    paths: &[String],
+    sizes: Option<&[u64]>,
+    config: &BraceConfig,
+    depth: usize,
+) -> String {
+    let Some(base) = config.base.as_deref().filter(|_| depth > 0) else {
+        return summarise_group(paths, sizes, config, depth);
+    };
+
+    let (mut inside, mut outside) = ((vec![], vec![]), (vec![], vec![]));
+    for (i, path) in paths.iter().enumerate() {
+        let (group, path) = match relative_to(path, base, config) {
+            Some(rel) => (&mut inside, rel.to_string()),
+            None => (&mut outside, path.clone()),
+        };
+        group.0.push(path);
+        group.1.extend(sizes.map(|sizes| sizes[i]));
+    }
+
+    let mut groups = vec![];
+    for (group, prefix) in [
+        (inside, base_prefix(config, true)),
+        (outside, String::new()),
+    ] {
+        if !group.0.is_empty() {
+            let sizes = sizes.map(|_| group.1.as_slice());
+            let summary = summarise_group(&group.0, sizes, config, depth);
+            groups.push(BraceExpr::Literal(format!("{}{}", prefix, summary)));
+        }
+    }
+    if groups.len() == 1 {
+        groups.remove(0).to_string()
+    } else {
+        BraceExpr::Alt(groups).to_string()
+    }
+}
+
+/// Build the trie of a group of paths and render it at the requested zoom depth
+fn summarise_group(
+    paths: &[String],
+    sizes: Option<&[u64]>,
+    config: &BraceConfig,
+    depth: usize,
+) -> String {
+    // A root shared by every path is kept in front, as by `render_group`, and
+    // not counted as a level
+    let sep = &config.path_separator;
+    if depth > 0
+        && !sep.is_empty()
+        && paths
+            .iter()
+            .all(|p| p.starts_with(sep.as_str()) && p != sep)
+    {
+        let relative: Vec<String> = paths.iter().map(|p| p[sep.len()..].to_string()).collect();
+        let summary = summarise_group(&relative, sizes, config, depth);
+        return format!("{}{}", escape_text(sep, config.escape), summary);
+    }
+
+    let (nodes, root_idx) = build_trie(paths, sep, config);
+    render_zoomed(&nodes, root_idx, sizes, sep, config, depth)
+}
+
+/// Render a node, cutting subtrees off at `remaining` levels below it
+fn render_zoomed(
+    nodes: &[Node],
+    idx: usize,
+    sizes: Option<&[u64]>,
+    sep: &str,
+    config: &BraceConfig,
+    remaining: usize,
+) -> String {
+    let node = &nodes[idx];
+    if node.children.is_empty() {
+        return node.label.clone();
+    }
+    if remaining == 0 {
+        let tally = tally(nodes, idx, sizes);
+        return match tally.bytes {
+            Some(bytes) => format!("{}({}; {})", node.label, tally.count, format_bytes(bytes)),
+            None => format!("{}({})", node.label, tally.count),
+        };
+    }
+
+    let mut items: Vec<BraceExpr> = node
+        .children
+        .values()
+        .map(|&child| {
+            BraceExpr::Literal(render_zoomed(
+                nodes,
+                child,
+                sizes,
+                sep,
+                config,
+                remaining.saturating_sub(1),
+            ))
+        })
+        .collect();
+
+    let has_trailing_sep_child = node
+        .children
+        .values()
+        .any(|&child| nodes[child].is_trailing_sep);
+    if node.is_leaf && !has_trailing_sep_child {
+        items.push(BraceExpr::empty());
+    }
+
+    compose_label_and_items(
+        &node.label,
+        sep,
+        &items,
+        config.max_brace_size,
+        config.sort_items,
+        config.direction,
+    )
+    .to_string()
+}
+
+/// Count the paths (and sum their sizes) in the subtree rooted at `idx`
+fn tally(nodes: &[Node], idx: usize, sizes: Option<&[u64]>) -> Tally {
+    let mut count = 0;
+    let mut bytes = 0;
+    let mut stack = vec![idx];
+    while let Some(i) = stack.pop() {
+        count += nodes[i].sources.len();
+        if let Some(sizes) = sizes {
+            bytes += nodes[i].sources.iter().map(|&s| sizes[s]).sum::<u64>();
+        }
+        stack.extend(nodes[i].children.values());
+    }
+    Tally {
+        count,
+        bytes: sizes.map(|_| bytes),
+    }
+}
+
+/// Human-readable byte size using binary units
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
@@ -1,67 +1,107 @@
 mod ordered_map;
 
+use super::case::CaseFolding;
+use super::escape::escape_text;
+use super::segment::{PathSegmenter, Segmenter};
 use crate::BraceConfig;
 use ordered_map::OrderedMap;
+use std::collections::HashMap;
 
 /// Trie node: uses IndexMap-like OrderedMap whose key is (label, ID)
 ///
 /// The ID allows us to treat duplicates as distinct nodes if not deduplicating.
-#[derive(Debug)]
+/// Keys hold the raw path segment (case-folded if configured), while `label`
+/// holds it as it is output (escaped according to the configured `EscapePolicy`).
+#[derive(Debug, Clone)]
 pub struct Node {
     pub label: String,
     pub children: OrderedMap<(String, usize), usize>,
     pub is_leaf: bool,
     pub is_trailing_sep: bool,
     pub depth: usize,
+    /// Indices of the input paths that end at this node
+    pub sources: Vec<usize>,
+}
+
+impl Node {
+    /// A node with no children that no path ends at
+    pub(crate) fn new(label: String, depth: usize) -> Node {
+        Node {
+            label,
+            children: OrderedMap::new(),
+            is_leaf: false,
+            is_trailing_sep: false,
+            depth,
+            sources: vec![],
+        }
+    }
 }
 
 /// Build trie of paths
 pub fn build_trie(paths: &[String], sep: &str, config: &BraceConfig) -> (Vec<Node>, usize) {
-    let mut nodes = vec![Node {
-        label: String::new(),
-        children: OrderedMap::new(),
-        is_leaf: false,
-        is_trailing_sep: false,
-        depth: 0,
-    }];
+    build_trie_with(paths, &PathSegmenter::new(sep, config), config)
+}
 
-    let mut next_id = 0;
+/// Build trie of paths split by a [`Segmenter`]
+pub fn build_trie_with(
+    paths: &[String],
+    segmenter: &dyn Segmenter,
+    config: &BraceConfig,
+) -> (Vec<Node>, usize) {
+    let segments: Vec<Vec<String>> = paths
+        .iter()
+        .map(|path| path_segments(path, segmenter, config))
+        .collect();
+    build_trie_from_segments(&segments, config)
+}
 
-    for path in paths {
-        let comps: Vec<String> = if config.allow_segment_split && !sep.is_empty() {
-            path.split(sep).map(|s| s.to_string()).collect()
-        } else {
-            // When segment split is disabled, still extract common prefix
-            let cur_path = path.as_str();
-            let mut components = Vec::new();
+/// Split a path into the segments that form its trie levels, in the configured direction
+pub(crate) fn path_segments(
+    path: &str,
+    segmenter: &dyn Segmenter,
+    config: &BraceConfig,
+) -> Vec<String> {
+    let mut segments: Vec<String> = segmenter
+        .segments(path)
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect();
+    config.direction.order(&mut segments);
+    if !config.allow_segment_split && segments.len() > 1 {
+        // When segment split is disabled, still extract the first segment as a common prefix
+        let mut rest = segments.split_off(1);
+        config.direction.order(&mut rest);
+        segments.push(rest.join(segmenter.separator()));
+    }
+    segments
+}
 
-            // Find common prefix with existing paths at root
-            if !sep.is_empty() && cur_path.contains(sep) {
-                let parts: Vec<&str> = cur_path.split(sep).collect();
-                if parts.len() > 1 {
-                    // Take first component as potential common prefix
-                    components.push(parts[0].to_string());
-                    components.push(parts[1..].join(sep));
-                } else {
-                    components.push(cur_path.to_string());
-                }
-            } else {
-                components.push(cur_path.to_string());
-            }
-            components
-        };
+/// Build trie of pre-segmented paths
+///
+/// A path whose last segment is empty ends with a separator.
+pub fn build_trie_from_segments(
+    segments: &[Vec<String>],
+    config: &BraceConfig,
+) -> (Vec<Node>, usize) {
+    let mut nodes = vec![Node::new(String::new(), 0)];
 
+    let mut next_id = 0;
+    // Spellings seen for each node and how often, when picking the most common
+    let mut spellings: HashMap<usize, Vec<(String, usize)>> = HashMap::new();
+
+    for (path_idx, comps) in segments.iter().enumerate() {
         let mut cur = 0;
         for (i, comp) in comps.iter().enumerate() {
             let is_last = i + 1 == comps.len();
 
             // Only add unique ID if not deduplicating AND this is the last component
+            let folded = config.case_folding.fold(comp);
             let key = if !config.deduplicate_inputs && is_last {
                 let id = next_id;
                 next_id += 1;
-                (comp.clone(), id)
+                (folded, id)
             } else {
-                (comp.clone(), 0) // Use 0 as a dummy ID for non-leaf nodes
+                (folded, 0) // Use 0 as a dummy ID for non-leaf nodes
             };
 
             let child_idx = if let Some(&idx) = nodes[cur].children.get(&key) {
@@ -69,22 +109,38 @@ pub fn build_trie(paths: &[String], sep: &str, config: &BraceConfig) -> (Vec<Nod
             } else {
                 let idx = nodes.len();
                 nodes[cur].children.insert(key, idx);
-                nodes.push(Node {
-                    label: comp.clone(),
-                    children: OrderedMap::new(),
-                    is_leaf: false,
-                    is_trailing_sep: false,
-                    depth: nodes[cur].depth + 1,
-                });
+                nodes.push(Node::new(
+                    escape_text(&config.case_folding.spell(comp), config.escape),
+                    nodes[cur].depth + 1,
+                ));
                 idx
             };
             cur = child_idx;
+            if config.case_folding == CaseFolding::MostCommon {
+                let seen = spellings.entry(cur).or_default();
+                match seen.iter_mut().find(|(spelling, _)| spelling == comp) {
+                    Some((_, count)) => *count += 1,
+                    None => seen.push((comp.clone(), 1)),
+                }
+            }
             if is_last {
                 nodes[cur].is_leaf = true;
+                nodes[cur].sources.push(path_idx);
                 // Mark as trailing separator if the component is empty AND it's the last one
-                nodes[cur].is_trailing_sep = comp.is_empty() && path.ends_with(sep);
+                nodes[cur].is_trailing_sep = comp.is_empty() && comps.len() > 1;
+            }
+        }
+    }
+
+    for (idx, seen) in spellings {
+        // Stable max: the first seen spelling wins ties
+        let mut best = &seen[0];
+        for candidate in &seen[1..] {
+            if candidate.1 > best.1 {
+                best = candidate;
             }
         }
+        nodes[idx].label = escape_text(&best.0, config.escape);
     }
 
     (nodes, 0)
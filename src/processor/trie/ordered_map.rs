@@ -1,5 +1,5 @@
 /// Simple ordered map that preserves insertion order
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct OrderedMap<K, V> {
     entries: Vec<(K, V)>,
 }
@@ -33,6 +33,17 @@ impl<K: PartialEq, V> OrderedMap<K, V> {
         }
     }
 
+    /// Remove a key, keeping the order of the rest
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let pos = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(pos).1)
+    }
+
+    /// Reorder the entries by a key of their values (stable)
+    pub fn sort_by_key<T: Ord>(&mut self, mut f: impl FnMut(&V) -> T) {
+        self.entries.sort_by_key(|(_, v)| f(v));
+    }
+
     /// Forward + reverse iteration support
     pub fn iter(&self) -> std::slice::Iter<'_, (K, V)> {
         self.entries.iter()
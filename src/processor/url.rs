@@ -0,0 +1,56 @@
+//! Splitting URLs into root, path, query and fragment segments
+
+/// Split a URL into its segments, each keeping its leading delimiter
+///
+/// `https://a.example.com/v1/x?page=2&sort=asc#top` becomes
+/// `["https://a.example.com", "/v1", "/x", "?page=2", "&sort=asc", "#top"]`.
+/// Without a `scheme://` the URL has no root segment. Joining the segments
+/// gives back the URL exactly.
+pub(crate) fn url_segments(url: &str) -> Vec<&str> {
+    let mut segments = vec![];
+
+    let mut start = 0;
+    if let Some(scheme_end) = url.find("://") {
+        let authority = scheme_end + 3;
+        start = url[authority..]
+            .find(['/', '?', '#'])
+            .map_or(url.len(), |i| authority + i);
+        segments.push(&url[..start]);
+    }
+
+    let fragment = url[start..].find('#').map_or(url.len(), |i| start + i);
+    let query = url[start..fragment]
+        .find('?')
+        .map_or(fragment, |i| start + i);
+
+    push_split(&mut segments, &url[start..query], '/');
+    push_split(&mut segments, &url[query..fragment], '&');
+    if fragment < url.len() {
+        segments.push(&url[fragment..]);
+    }
+    segments
+}
+
+/// Split text before each occurrence of `delimiter`, skipping empty pieces
+fn push_split<'a>(segments: &mut Vec<&'a str>, text: &'a str, delimiter: char) {
+    let mut start = 0;
+    for (i, _) in text.match_indices(delimiter) {
+        if i > start {
+            segments.push(&text[start..i]);
+        }
+        start = i;
+    }
+    if start < text.len() {
+        segments.push(&text[start..]);
+    }
+}
+
+/// The delimiter every item starts with, if they share one
+///
+/// Hoisting it out of the group turns `{/users,/groups}` into `/{users,groups}`.
+pub(crate) fn shared_delimiter(items: &[String]) -> Option<char> {
+    let first = items.first()?.chars().next()?;
+    let shared =
+        ['/', '?', '&', '#'].contains(&first) && items.iter().all(|item| item.starts_with(first));
+    shared.then_some(first)
+}
@@ -0,0 +1,95 @@
+//! Round-trip verification of compressed output
+
+use super::ast::BraceExpr;
+use super::escape::escape_text;
+use super::expansion::expand_braces_with;
+use super::BraceConfig;
+use crate::error::{BraceError, Result};
+use std::collections::HashMap;
+
+/// What to do after compressing, to check the output expands back to the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifyMode {
+    /// Trust the output without checking it (default).
+    #[default]
+    Off,
+
+    /// Return [`BraceError::VerificationFailed`] if the output does not round-trip.
+    Error,
+
+    /// Fall back to a flat `{p1,p2,...}` listing if the output does not round-trip.
+    Fallback,
+}
+
+/// Check that `expr` expands back to `paths`, according to `config.verify`
+///
+/// Expansion is compared to the paths as a multiset, or as a sequence when
+/// order is preserved (`preserve_order_within_braces` without `sort_items`),
+/// ignoring case when `case_folding` is enabled.
+pub(crate) fn verify_output(
+    expr: BraceExpr,
+    paths: &[String],
+    config: &BraceConfig,
+) -> Result<BraceExpr> {
+    if config.verify == VerifyMode::Off {
+        return Ok(expr);
+    }
+
+    let fold = |paths: &[String]| -> Vec<String> {
+        paths.iter().map(|p| config.case_folding.fold(p)).collect()
+    };
+    let expanded = fold(&expand_braces_with(&expr.to_string(), config.escape));
+    let folded = fold(paths);
+    let ordered = config.preserve_order_within_braces && !config.sort_items;
+    let matches = if ordered {
+        expanded == folded
+    } else {
+        counts(&expanded) == counts(&folded)
+    };
+    if matches {
+        return Ok(expr);
+    }
+
+    match config.verify {
+        VerifyMode::Fallback => Ok(flat_listing(paths, config)),
+        _ => Err(BraceError::VerificationFailed {
+            missing: difference(&folded, &expanded),
+            unexpected: difference(&expanded, &folded),
+        }),
+    }
+}
+
+/// Encode paths as a single flat group, which always round-trips
+fn flat_listing(paths: &[String], config: &BraceConfig) -> BraceExpr {
+    let mut items: Vec<BraceExpr> = paths
+        .iter()
+        .map(|p| BraceExpr::Literal(escape_text(p, config.escape)))
+        .collect();
+    if items.len() == 1 {
+        items.remove(0)
+    } else {
+        BraceExpr::Alt(items)
+    }
+}
+
+/// Occurrence count of each path
+fn counts(paths: &[String]) -> HashMap<&str, usize> {
+    let mut out = HashMap::new();
+    for p in paths {
+        *out.entry(p.as_str()).or_insert(0) += 1;
+    }
+    out
+}
+
+/// Paths in `a` that are not matched by an occurrence in `b`, in order
+fn difference(a: &[String], b: &[String]) -> Vec<String> {
+    let mut remaining = counts(b);
+    let mut out = vec![];
+    for p in a {
+        match remaining.get_mut(p.as_str()) {
+            Some(n) if *n > 0 => *n -= 1,
+            _ => out.push(p.clone()),
+        }
+    }
+    out
+}
@@ -0,0 +1,63 @@
+//! Windows path mode: drive and UNC roots, and PowerShell output
+
+use super::expansion::expand_braces_with;
+use super::style::resolve_style;
+use super::BraceConfig;
+
+/// The drive or UNC root at the start of a Windows path, without the separator after it
+///
+/// Recognises `C:`, `\\server\share`, and the `\\?\` and `\\.\` device forms
+/// such as `\\?\C:` and `\\?\UNC\server\share`.
+pub(crate) fn windows_root(path: &str) -> Option<&str> {
+    let parts: Vec<&str> = path.split('\\').collect();
+    let root_parts = if let Some(rest) = path.strip_prefix("\\\\") {
+        if rest.is_empty() || parts[2].is_empty() {
+            return None;
+        }
+        let wanted = match parts[2] {
+            "?" | "." if parts.get(3).is_some_and(|p| p.eq_ignore_ascii_case("UNC")) => 6,
+            _ => 4,
+        };
+        wanted.min(parts.len())
+    } else {
+        let drive = parts[0].as_bytes();
+        if drive.len() == 2 && drive[0].is_ascii_alphabetic() && drive[1] == b':' {
+            1
+        } else {
+            return None;
+        }
+    };
+    let len = parts[..root_parts].iter().map(|p| p.len()).sum::<usize>() + root_parts - 1;
+    Some(&path[..len])
+}
+
+/// Write a braces expression as a PowerShell array of its paths
+///
+/// PowerShell and cmd have no brace expansion, so this lists every path as a
+/// single-quoted string: `@('C:\src\a.rs', 'C:\src\b.rs')`. The expression
+/// is expanded with the escaping policy of `config`, as output by
+/// [`brace_paths`](crate::brace_paths).
+///
+/// # Example
+/// ```
+/// use braces::{brace_paths, powershell_array, BraceConfig, PathStyle};
+///
+/// let config = BraceConfig {
+///     path_style: PathStyle::Windows,
+///     ..Default::default()
+/// };
+/// let result = brace_paths(&[r"C:\src\a.rs", r"C:\src\it's.rs"], &config).unwrap();
+/// assert_eq!(result, r"C:\src\{a,it's}.rs");
+/// assert_eq!(
+///     powershell_array(&result, &config),
+///     r"@('C:\src\a.rs', 'C:\src\it''s.rs')"
+/// );
+/// ```
+pub fn powershell_array(expr: &str, config: &BraceConfig) -> String {
+    let escape = resolve_style(config).escape;
+    let items: Vec<String> = expand_braces_with(expr, escape)
+        .iter()
+        .map(|p| format!("'{}'", p.replace('\'', "''")))
+        .collect();
+    format!("@({})", items.join(", "))
+}
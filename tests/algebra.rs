@@ -0,0 +1,145 @@
+use braces::{
+    combine_braces, combine_paths, BraceConfig, BraceError, CaseFolding, EscapePolicy,
+    Normalisation, PathStyle, SetOperation,
+};
+
+fn sorted() -> BraceConfig {
+    BraceConfig {
+        sort_items: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_union_keeps_first_set_order() {
+    let a = vec!["src/c.rs", "src/a.rs"];
+    let b = vec!["src/a.rs", "src/b.rs"];
+    let result = combine_paths(&a, &b, SetOperation::Union, &BraceConfig::default()).unwrap();
+    assert_eq!(result.as_deref(), Some("src/{c,a,b}.rs"));
+}
+
+#[test]
+fn test_intersection_and_difference() {
+    let a = "{src/{a,b,c},tests/{a,b}}.rs";
+    let b = "{src/{b,c,d},tests/b}.rs";
+    let both = combine_braces(a, b, SetOperation::Intersection, &sorted()).unwrap();
+    assert_eq!(both.as_deref(), Some("{src/{b,c},tests/b}.rs"));
+    let left = combine_braces(a, b, SetOperation::Difference, &sorted()).unwrap();
+    assert_eq!(left.as_deref(), Some("{src,tests}/a.rs"));
+}
+
+#[test]
+fn test_empty_results_and_operands() {
+    let config = BraceConfig::default();
+    let none = combine_braces("a/{x,y}", "b/{x,y}", SetOperation::Intersection, &config);
+    assert_eq!(none.unwrap(), None);
+
+    let empty: Vec<&str> = vec![];
+    let result = combine_paths(&empty, &["a", "b"], SetOperation::Union, &config).unwrap();
+    assert_eq!(result.as_deref(), Some("{a,b}"));
+    let result = combine_paths(&["a"], &empty, SetOperation::Difference, &config).unwrap();
+    assert_eq!(result.as_deref(), Some("a"));
+}
+
+#[test]
+fn test_paths_match_after_normalisation() {
+    let config = BraceConfig {
+        normalisation: Normalisation {
+            remove_dot_segments: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = combine_paths(
+        &["src/./a.rs", "src/b.rs"],
+        &["src/a.rs"],
+        SetOperation::Difference,
+        &config,
+    )
+    .unwrap();
+    assert_eq!(result.as_deref(), Some("src/b.rs"));
+}
+
+#[test]
+fn test_paths_match_with_case_folding() {
+    let config = BraceConfig {
+        case_folding: CaseFolding::FirstSeen,
+        ..Default::default()
+    };
+    let result = combine_paths(
+        &["Docs/A.md", "src/x.rs"],
+        &["docs/a.md"],
+        SetOperation::Intersection,
+        &config,
+    )
+    .unwrap();
+    assert_eq!(result.as_deref(), Some("Docs/A.md"));
+}
+
+#[test]
+fn test_invalid_expression_is_an_error() {
+    let err = combine_braces("a/{b", "a/b", SetOperation::Union, &BraceConfig::default());
+    assert!(matches!(err, Err(BraceError::InvalidBraceSyntax { .. })));
+}
+
+#[test]
+fn test_windows_expressions_read_with_resolved_escaping() {
+    let config = BraceConfig {
+        path_style: PathStyle::Windows,
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let result = combine_braces(r"C:\x\{a,b}", r"C:\x\b", SetOperation::Difference, &config);
+    assert_eq!(result.unwrap().as_deref(), Some(r"C:\x\a"));
+}
+
+#[test]
+fn test_union_is_not_expanded() {
+    let config = BraceConfig::default();
+    let union = |a, b| combine_braces(a, b, SetOperation::Union, &config).unwrap();
+    assert_eq!(
+        union("src/{a,b}.rs", "src/{b,c}.rs").as_deref(),
+        Some("src/{a,b,c}.rs")
+    );
+    assert_eq!(union("a/b", "a/b/c").as_deref(), Some("a/b{,/c}"));
+    assert_eq!(union("x", "x").as_deref(), Some("x"));
+
+    // 2^40 paths on one side, and a sequence of a billion on the other
+    let pairs = format!("out/{}", "{a,b}".repeat(40));
+    let expected = format!("out/{{{},{{1..1000000000}}}}", "{a,b}".repeat(40));
+    assert_eq!(
+        union(&pairs, "out/{1..1000000000}").as_deref(),
+        Some(expected.as_str())
+    );
+}
+
+#[test]
+fn test_intersection_and_difference_with_large_operand() {
+    let config = BraceConfig::default();
+    let huge = "log/{1..1000000000}.txt";
+    let small = "log/{12,x,7}.txt";
+    let both = combine_braces(huge, small, SetOperation::Intersection, &config).unwrap();
+    assert_eq!(both.as_deref(), Some("log/{12,7}.txt"));
+    let both = combine_braces(small, huge, SetOperation::Intersection, &config).unwrap();
+    assert_eq!(both.as_deref(), Some("log/{12,7}.txt"));
+    let left = combine_braces(small, huge, SetOperation::Difference, &config).unwrap();
+    assert_eq!(left.as_deref(), Some("log/x.txt"));
+
+    let pairs = "{a,b}".repeat(40);
+    let one = "ab".repeat(20);
+    let both = combine_braces(&pairs, &one, SetOperation::Intersection, &config).unwrap();
+    assert_eq!(both, Some(one));
+}
+
+#[test]
+fn test_union_rejects_unwritable_text() {
+    let err = combine_braces("a,b", "c", SetOperation::Union, &BraceConfig::default());
+    assert!(matches!(err, Err(BraceError::InvalidBraceInput { .. })));
+
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let union = combine_braces(r"a\,b", "c", SetOperation::Union, &config).unwrap();
+    assert_eq!(union.as_deref(), Some(r"{a\,b,c}"));
+}
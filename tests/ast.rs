@@ -0,0 +1,88 @@
+use braces::{
+    brace_paths, brace_paths_expr, expand_braces_with, BraceConfig, BraceExpr, EscapePolicy,
+    VerifyMode,
+};
+
+fn lit(text: &str) -> BraceExpr {
+    BraceExpr::Literal(text.to_string())
+}
+
+#[test]
+fn test_display_matches_brace_paths() {
+    let cases: Vec<Vec<&str>> = vec![
+        vec!["src/a.rs", "src/b.rs", "tests/c.rs"],
+        vec!["/etc/hosts", "/etc/passwd", "/var/log"],
+        vec!["a", "a/b", "a/c/"],
+        vec!["lib/x.rs", "lib/y.rs", "README.md", "lib/z/w.rs"],
+    ];
+    let config = BraceConfig::default();
+    for paths in cases {
+        let expr = brace_paths_expr(&paths, &config).unwrap();
+        assert_eq!(expr.to_string(), brace_paths(&paths, &config).unwrap());
+    }
+}
+
+#[test]
+fn test_tree_structure() {
+    let expr = brace_paths_expr(&["src/a.rs", "tests/b.rs"], &BraceConfig::default()).unwrap();
+    assert_eq!(
+        expr,
+        BraceExpr::Concat(vec![
+            BraceExpr::Alt(vec![lit("src/a"), lit("tests/b")]),
+            lit(".rs"),
+        ])
+    );
+}
+
+#[test]
+fn test_expansion_count() {
+    let paths = vec!["a/b/c", "a/b/d", "a/e", "f"];
+    let expr = brace_paths_expr(&paths, &BraceConfig::default()).unwrap();
+    assert_eq!(expr.expansion_count(), paths.len());
+}
+
+#[test]
+fn test_concat_merges_literals_and_flattens() {
+    let expr = BraceExpr::concat([
+        lit("a"),
+        BraceExpr::Concat(vec![lit("b"), BraceExpr::Alt(vec![lit("c"), lit("d")])]),
+        BraceExpr::empty(),
+        lit("e"),
+    ]);
+    assert_eq!(
+        expr,
+        BraceExpr::Concat(vec![
+            lit("ab"),
+            BraceExpr::Alt(vec![lit("c"), lit("d")]),
+            lit("e"),
+        ])
+    );
+    assert_eq!(expr.to_string(), "ab{c,d}e");
+    assert!(BraceExpr::concat([BraceExpr::empty(), lit("")]).is_empty());
+}
+
+#[test]
+fn test_literals_hold_escaped_text() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let paths = vec!["a,b/x", "a,b/y"];
+    let expr = brace_paths_expr(&paths, &config).unwrap();
+    assert_eq!(expr.to_string(), r"a\,b/{x,y}");
+    assert_eq!(
+        expand_braces_with(&expr.to_string(), EscapePolicy::Backslash),
+        paths
+    );
+}
+
+#[test]
+fn test_verified_expr_is_unchanged() {
+    let config = BraceConfig {
+        verify: VerifyMode::Fallback,
+        ..Default::default()
+    };
+    let expr = brace_paths_expr(&["x/a", "x/b"], &config).unwrap();
+    assert_eq!(expr.expansion_count(), 2);
+    assert_eq!(expr.to_string(), "x/{a,b}");
+}
@@ -0,0 +1,110 @@
+use braces::{
+    brace_paths, BaseDisplay, BraceConfig, BraceError, OutsideBase, PathStyle, VerifyMode,
+};
+
+fn based(base: &str, outside_base: OutsideBase, base_display: BaseDisplay) -> BraceConfig {
+    BraceConfig {
+        base: Some(base.to_string()),
+        outside_base,
+        base_display,
+        verify: VerifyMode::Error,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_base_hidden() {
+    let paths = vec!["/home/ci/repo/src/a.rs", "/home/ci/repo/src/b.rs"];
+    let config = based("/home/ci/repo/", OutsideBase::Reject, BaseDisplay::Hide);
+    assert_eq!(brace_paths(&paths, &config).unwrap(), "src/{a,b}.rs");
+}
+
+#[test]
+fn test_base_as_prefix() {
+    let paths = vec!["/home/ci/repo/src/a.rs", "/home/ci/repo/tests/b.rs"];
+    let config = based("/home/ci/repo", OutsideBase::Reject, BaseDisplay::Prefix);
+    let result = brace_paths(&paths, &config).unwrap();
+    assert_eq!(result, "/home/ci/repo/{src/a,tests/b}.rs");
+}
+
+#[test]
+fn test_base_as_variable() {
+    let paths = vec!["/repo/a.rs", "/repo/b.rs"];
+    let config = based(
+        "/repo",
+        OutsideBase::Reject,
+        BaseDisplay::Variable("$ROOT".into()),
+    );
+    assert_eq!(brace_paths(&paths, &config).unwrap(), "$ROOT/{a,b}.rs");
+}
+
+#[test]
+fn test_outside_rejected() {
+    let paths = vec!["/repo/a.rs", "/repository/b.rs"];
+    let config = based("/repo", OutsideBase::Reject, BaseDisplay::Hide);
+    assert_eq!(
+        brace_paths(&paths, &config),
+        Err(BraceError::OutsideBase {
+            path: "/repository/b.rs".to_string(),
+            base: "/repo".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_outside_kept_in_separate_group() {
+    let paths = vec![
+        "/repo/a.rs",
+        "/usr/include/x.h",
+        "/repo/b.rs",
+        "/usr/include/y.h",
+    ];
+    let config = based("/repo", OutsideBase::KeepAbsolute, BaseDisplay::Hide);
+    let result = brace_paths(&paths, &config).unwrap();
+    assert_eq!(result, "{{a,b}.rs,/usr/include/{x,y}.h}");
+}
+
+#[test]
+fn test_only_outside_paths() {
+    let paths = vec!["/usr/a", "/usr/b"];
+    let config = based("/repo", OutsideBase::KeepAbsolute, BaseDisplay::Hide);
+    assert_eq!(brace_paths(&paths, &config).unwrap(), "/usr/{a,b}");
+}
+
+#[test]
+fn test_base_itself() {
+    let paths = vec!["/repo", "/repo/a"];
+    let config = based("/repo", OutsideBase::Reject, BaseDisplay::Prefix);
+    assert_eq!(brace_paths(&paths, &config).unwrap(), "/repo/{.,a}");
+}
+
+#[test]
+fn test_absolute_paths_keep_root() {
+    let paths = vec!["/etc/hosts", "/usr/bin/env"];
+    let result = brace_paths(&paths, &BraceConfig::default()).unwrap();
+    assert_eq!(result, "/{etc/hosts,usr/bin/env}");
+}
+
+#[test]
+fn test_outside_path_shown_as_inside_rejected() {
+    let paths = vec!["/repo/a.rs", "a.rs"];
+    let config = based("/repo", OutsideBase::KeepAbsolute, BaseDisplay::Hide);
+    assert!(matches!(
+        brace_paths(&paths, &config),
+        Err(BraceError::InvalidBraceInput { .. })
+    ));
+
+    // Shown with the base in front, the two are distinct
+    let config = based("/repo", OutsideBase::KeepAbsolute, BaseDisplay::Prefix);
+    assert_eq!(brace_paths(&paths, &config).unwrap(), "{/repo/a.rs,a.rs}");
+}
+
+#[test]
+fn test_windows_base_ignores_case() {
+    let paths = vec![r"c:\repo\src\a.rs", r"C:\Repo\src\b.rs"];
+    let config = BraceConfig {
+        path_style: PathStyle::Windows,
+        ..based(r"C:\Repo", OutsideBase::Reject, BaseDisplay::Hide)
+    };
+    assert_eq!(brace_paths(&paths, &config).unwrap(), r"src\{a,b}.rs");
+}
@@ -50,3 +50,12 @@ fn test_complex_paths() {
         "{x/{a,b,c},y/a}.rs",
     );
 }
+
+#[test]
+fn test_common_suffix_with_shorter_later_path() {
+    assert_braces_default(vec!["src/long.rs", "a.rs"], "{src/long,a}.rs");
+    assert_braces_default(
+        vec!["a-long-name.tar.gz", "b.tar.gz"],
+        "{a-long-name,b}.tar.gz",
+    );
+}
@@ -0,0 +1,138 @@
+use braces::{brace_paths, brace_paths_budgeted, BraceConfig, OutsideBase};
+
+#[test]
+fn test_budget_not_needed() {
+    let paths = vec!["a/b.rs", "a/c.rs"];
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 80).unwrap();
+    assert_eq!(result.text, "a/{b,c}.rs");
+    assert!(!result.lossy);
+}
+
+#[test]
+fn test_budget_collapses_deepest_subtree() {
+    let paths = vec![
+        "src/lib.rs",
+        "src/util/a.rs",
+        "src/util/b.rs",
+        "src/util/c.rs",
+        "src/util/d.rs",
+        "src/util/e.rs",
+        "src/util/f.rs",
+        "tests/x.rs",
+    ];
+    let full = brace_paths(&paths, &BraceConfig::default()).unwrap();
+    assert_eq!(full, "{src/{lib,util/{a,b,c,d,e,f}},tests/x}.rs");
+
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 40).unwrap();
+    assert_eq!(result.text, "{src/{lib,util/{…6 files}},tests/x}.rs");
+    assert!(result.lossy);
+}
+
+#[test]
+fn test_budget_collapses_upwards() {
+    let paths = vec![
+        "src/lib.rs",
+        "src/util/a.rs",
+        "src/util/b.rs",
+        "src/util/c.rs",
+        "tests/x.rs",
+    ];
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 30).unwrap();
+    assert_eq!(result.text, "{src/{…4 files},tests/x}.rs");
+    assert!(result.lossy);
+}
+
+#[test]
+fn test_budget_truncates_outer_group() {
+    let paths: Vec<String> = (0..40).map(|i| format!("f{}", i)).collect();
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 12).unwrap();
+    assert_eq!(result.text, "{f0,f1,…+38}");
+    assert!(result.lossy);
+}
+
+#[test]
+fn test_budget_summarises_everything_as_last_resort() {
+    let paths = vec!["some/long/path/a", "some/long/path/b"];
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 5).unwrap();
+    assert_eq!(result.text, "{…2 files}");
+    assert!(result.lossy);
+}
+
+#[test]
+fn test_budget_summarises_single_path() {
+    let paths = vec!["averyveryverylongpath/x/y/z.rs"];
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 5).unwrap();
+    assert_eq!(result.text, "{…1 file}");
+    assert!(result.lossy);
+}
+
+#[test]
+fn test_budget_summary_drops_shared_suffix() {
+    let paths = vec![
+        "a/some/very/long/shared/suffix.rs",
+        "b/some/very/long/shared/suffix.rs",
+    ];
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 12).unwrap();
+    assert_eq!(result.text, "{…2 files}");
+    assert!(result.lossy);
+}
+
+#[test]
+fn test_budget_keeps_shared_root() {
+    let paths = vec![
+        "/src/lib.rs",
+        "/src/util/a.rs",
+        "/src/util/b.rs",
+        "/src/util/c.rs",
+        "/src/util/d.rs",
+        "/src/util/e.rs",
+        "/src/util/f.rs",
+        "/tests/x.rs",
+    ];
+    let full = brace_paths(&paths, &BraceConfig::default()).unwrap();
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 80).unwrap();
+    assert_eq!(result.text, full);
+    assert!(!result.lossy);
+
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 41).unwrap();
+    assert_eq!(result.text, "/{src/{lib,util/{…6 files}},tests/x}.rs");
+
+    // Counts include paths in groups elided earlier
+    let result = brace_paths_budgeted(&paths, &BraceConfig::default(), 30).unwrap();
+    assert_eq!(result.text, "/{src/{…7 files},tests/x}.rs");
+}
+
+#[test]
+fn test_budget_relative_to_base() {
+    let paths = vec!["/repo/src/a.rs", "/repo/src/b.rs", "/repo/src/c.rs"];
+    let config = BraceConfig {
+        base: Some("/repo".into()),
+        ..Default::default()
+    };
+    let full = brace_paths(&paths, &config).unwrap();
+    assert_eq!(full, "src/{a,b,c}.rs");
+
+    let result = brace_paths_budgeted(&paths, &config, 80).unwrap();
+    assert_eq!(result.text, full);
+    assert!(!result.lossy);
+}
+
+#[test]
+fn test_budget_keeps_paths_outside_base() {
+    let paths = vec!["/repo/x/a.rs", "/repo/x/b.rs", "/y/c.rs"];
+    let config = BraceConfig {
+        base: Some("/repo".into()),
+        outside_base: OutsideBase::KeepAbsolute,
+        ..Default::default()
+    };
+    let full = brace_paths(&paths, &config).unwrap();
+    assert_eq!(full, "{x/{a,b}.rs,/y/c.rs}");
+
+    let result = brace_paths_budgeted(&paths, &config, 80).unwrap();
+    assert_eq!(result.text, full);
+    assert!(!result.lossy);
+
+    let result = brace_paths_budgeted(&paths, &config, 16).unwrap();
+    assert_eq!(result.text, "{x/{a,b}.rs,…+1}");
+    assert!(result.lossy);
+}
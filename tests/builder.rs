@@ -0,0 +1,232 @@
+use braces::{
+    brace_paths, BraceBuilder, BraceConfig, BraceError, CaseFolding, Direction, EscapePolicy,
+    VerifyMode,
+};
+
+/// Check the builder renders what a full recompute gives for its paths
+fn assert_matches_full(builder: &mut BraceBuilder, config: &BraceConfig) {
+    let paths: Vec<String> = builder.paths().map(String::from).collect();
+    let expected = brace_paths(&paths, config);
+    assert_eq!(builder.render(), expected, "Bracing {:?}", paths);
+}
+
+/// A small deterministic generator, so failures can be replayed
+struct Lcg(u64);
+
+impl Lcg {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % n
+    }
+}
+
+/// Insert and remove paths at random, comparing against a full recompute after each change
+fn check_random_edits(config: &BraceConfig, pool: &[&str], seed: u64) {
+    let mut rng = Lcg(seed);
+    let mut builder = BraceBuilder::new(config);
+    for _ in 0..200 {
+        let present: Vec<String> = builder.paths().map(String::from).collect();
+        if present.is_empty() || rng.below(3) > 0 {
+            builder.insert(pool[rng.below(pool.len())]).unwrap();
+        } else {
+            assert!(builder.remove(&present[rng.below(present.len())]));
+        }
+        assert_matches_full(&mut builder, config);
+    }
+}
+
+const POOL: &[&str] = &[
+    "src/a.rs",
+    "src/b.rs",
+    "src/lib/mod.rs",
+    "src/lib/util.rs",
+    "src/lib",
+    "Src/A.rs",
+    "SRC/a.rs",
+    "docs/guide.md",
+    "docs/",
+    "README.md",
+    "/usr/lib/x.so",
+    "/usr/lib/y.so",
+    "//net/share",
+    "a",
+    "a/b",
+    "a/b/c",
+    "",
+];
+
+#[test]
+fn test_matches_full_recompute() {
+    let config = BraceConfig::default();
+    let mut builder = BraceBuilder::new(&config);
+    for path in ["src/a.rs", "src/b.rs", "src/lib/mod.rs", "docs/guide.md"] {
+        builder.insert(path).unwrap();
+        assert_matches_full(&mut builder, &config);
+    }
+    assert_eq!(
+        builder.render().unwrap(),
+        "{src/{a.rs,b.rs,lib/mod.rs},docs/guide.md}"
+    );
+}
+
+#[test]
+fn test_remove_restores_order() {
+    let config = BraceConfig::default();
+    let mut builder = BraceBuilder::new(&config);
+    for path in ["x/1", "y/1", "x/2", "z/1"] {
+        builder.insert(path).unwrap();
+    }
+    assert!(builder.remove("x/1"));
+    // `x` now first appears after `y`
+    assert_eq!(builder.render().unwrap(), "{y/1,x/2,z/1}");
+    assert_matches_full(&mut builder, &config);
+    assert!(!builder.remove("x/1"));
+}
+
+#[test]
+fn test_duplicate_takes_over_when_removed() {
+    let config = BraceConfig::default();
+    let mut builder = BraceBuilder::new(&config);
+    for path in ["a/x", "b/y", "a/x", "a/z"] {
+        builder.insert(path).unwrap();
+    }
+    assert_eq!(builder.render().unwrap(), "{a/{x,z},b/y}");
+    builder.remove("a/x");
+    assert_eq!(builder.len(), 3);
+    assert_eq!(builder.render().unwrap(), "{b/y,a/{x,z}}");
+    assert_matches_full(&mut builder, &config);
+}
+
+#[test]
+fn test_common_suffix_changes() {
+    let config = BraceConfig::default();
+    let mut builder = BraceBuilder::new(&config);
+    builder.insert("src/a.rs").unwrap();
+    assert_eq!(builder.render().unwrap(), "src/a.rs");
+    builder.insert("src/b.rs").unwrap();
+    assert_eq!(builder.render().unwrap(), "src/{a,b}.rs");
+    builder.insert("src/c.md").unwrap();
+    assert_eq!(builder.render().unwrap(), "src/{a.rs,b.rs,c.md}");
+    builder.remove("src/c.md");
+    assert_eq!(builder.render().unwrap(), "src/{a,b}.rs");
+}
+
+#[test]
+fn test_empty_builder() {
+    let config = BraceConfig::default();
+    let mut builder = BraceBuilder::new(&config);
+    assert!(builder.is_empty());
+    assert_eq!(builder.render(), Err(BraceError::EmptyInput));
+    builder.insert("a").unwrap();
+    builder.remove("a");
+    assert_eq!(builder.render(), Err(BraceError::EmptyInput));
+}
+
+#[test]
+fn test_rejected_path_is_not_added() {
+    let config = BraceConfig::default();
+    let mut builder = BraceBuilder::new(&config);
+    builder.insert("a/b").unwrap();
+    assert!(matches!(
+        builder.insert("a\\c"),
+        Err(BraceError::MixedSeparators { .. })
+    ));
+    assert!(builder.insert("a/{c}").is_err());
+    assert_eq!(builder.paths().collect::<Vec<_>>(), vec!["a/b"]);
+}
+
+#[test]
+fn test_random_edits_default() {
+    check_random_edits(&BraceConfig::default(), POOL, 1);
+    check_random_edits(&BraceConfig::default(), POOL, 2);
+}
+
+#[test]
+fn test_random_edits_case_folding() {
+    for case_folding in [
+        CaseFolding::FirstSeen,
+        CaseFolding::MostCommon,
+        CaseFolding::Lowercase,
+    ] {
+        let config = BraceConfig {
+            case_folding,
+            ..Default::default()
+        };
+        check_random_edits(&config, POOL, 3);
+    }
+}
+
+#[test]
+fn test_random_edits_keeping_duplicates() {
+    let config = BraceConfig {
+        deduplicate_inputs: false,
+        ..Default::default()
+    };
+    check_random_edits(&config, POOL, 4);
+}
+
+#[test]
+fn test_random_edits_other_settings() {
+    let configs = [
+        BraceConfig {
+            allow_stem_split: true,
+            sort_items: true,
+            ..Default::default()
+        },
+        BraceConfig {
+            allow_segment_split: false,
+            disallow_empty_braces: true,
+            ..Default::default()
+        },
+        BraceConfig {
+            stable_output: true,
+            max_depth: 1,
+            ..Default::default()
+        },
+        BraceConfig {
+            direction: Direction::RightToLeft,
+            max_brace_size: Some(2),
+            ..Default::default()
+        },
+        BraceConfig {
+            verify: VerifyMode::Fallback,
+            ..Default::default()
+        },
+    ];
+    for (seed, config) in configs.iter().enumerate() {
+        check_random_edits(config, POOL, 10 + seed as u64);
+    }
+}
+
+#[test]
+fn test_random_edits_reprocessing_braces() {
+    let config = BraceConfig {
+        reprocess_braces: true,
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let pool = [
+        "src/{a,b}.rs",
+        "src/a.rs",
+        "src/c\\,d.rs",
+        "lib/{x,y/z}",
+        "lib/x",
+    ];
+    check_random_edits(&config, &pool, 5);
+}
+
+#[test]
+fn test_base_directory() {
+    let config = BraceConfig {
+        base: Some("/repo".to_string()),
+        ..Default::default()
+    };
+    let mut builder = BraceBuilder::new(&config);
+    builder.insert("/repo/src/a.rs").unwrap();
+    builder.insert("/repo/src/b.rs").unwrap();
+    assert_eq!(builder.render().unwrap(), "src/{a,b}.rs");
+    assert!(builder.insert("/usr/c.h").is_err());
+}
@@ -0,0 +1,104 @@
+use braces::{canonicalize, equivalent, BraceConfig, BraceError, CaseFolding, EscapePolicy};
+
+#[test]
+fn test_canonical_form_ignores_order_and_duplicates() {
+    let config = BraceConfig::default();
+    let forms = ["a/{b,c}", "{a/c,a/b}", "a/{c,b,b}", "{a/b,a/{c,b}}"];
+    for form in forms {
+        assert_eq!(canonicalize(form, &config).unwrap(), "a/{b,c}", "{}", form);
+    }
+}
+
+#[test]
+fn test_canonical_form_is_idempotent() {
+    let config = BraceConfig::default();
+    let once = canonicalize("{src/{z,a}.rs,tests/x.rs,src/m.rs}", &config).unwrap();
+    assert_eq!(once, "{src/{a,m,z},tests/x}.rs");
+    assert_eq!(canonicalize(&once, &config).unwrap(), once);
+}
+
+#[test]
+fn test_canonical_form_keeps_escapes() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let canonical = canonicalize(r"{'x,y',a}", &config).unwrap();
+    assert_eq!(canonical, r"{a,x\,y}");
+}
+
+#[test]
+fn test_equivalent_by_structure() {
+    let config = BraceConfig::default();
+    assert!(equivalent("a/{b,{c,d}}.rs", "a/{d,c,b,b}.rs", &config).unwrap());
+    assert!(equivalent("{x}", "x", &config).unwrap());
+}
+
+#[test]
+fn test_equivalent_by_expansion() {
+    let config = BraceConfig::default();
+    assert!(equivalent("a/{b,c}", "{a/c,a/b}", &config).unwrap());
+    assert!(equivalent("{a,b}{1,2}", "{a1,a2,b1,b2}", &config).unwrap());
+    assert!(!equivalent("{a,b}{1,2}", "{a1,a2,b1}", &config).unwrap());
+}
+
+#[test]
+fn test_equivalent_across_escapes() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    assert!(equivalent(r"{a\,b,c}", "{c,'a,b'}", &config).unwrap());
+}
+
+#[test]
+fn test_invalid_syntax_is_an_error() {
+    let config = BraceConfig::default();
+    let err = equivalent("a/{b,c", "a/b", &config).unwrap_err();
+    assert!(matches!(err, BraceError::InvalidBraceSyntax { .. }));
+    assert!(canonicalize("a}", &config).is_err());
+}
+
+#[test]
+fn test_equivalent_without_expanding() {
+    let config = BraceConfig::default();
+    // 2^40 paths each, so expanding either side is out of the question
+    let pairs = "{a,b}".repeat(40);
+    let quads = "{aa,ab,ba,bb}".repeat(20);
+    assert!(equivalent(&pairs, &quads, &config).unwrap());
+
+    let missing = format!("{}{{aa,ab,ba}}", "{aa,ab,ba,bb}".repeat(19));
+    assert!(!equivalent(&pairs, &missing, &config).unwrap());
+}
+
+#[test]
+fn test_equivalent_with_empty_alternatives() {
+    let config = BraceConfig::default();
+    assert!(equivalent("a{,b}", "{ab,a}", &config).unwrap());
+    assert!(equivalent("src/{a,b/}{,c}", "src/{a,ac,b/,b/c}", &config).unwrap());
+    assert!(!equivalent("a{,b}", "{ab,a,}", &config).unwrap());
+}
+
+#[test]
+fn test_equivalent_sequences_without_expanding() {
+    let config = BraceConfig::default();
+    let digits = format!("{{1..9}}{}", "{,{0..9}}".repeat(8));
+    assert!(equivalent("{1..999999999}", &digits, &config).unwrap());
+    assert!(!equivalent("{1..1000000000}", &digits, &config).unwrap());
+    assert!(equivalent("{01..12}", "{0{1..9},1{0..2}}", &config).unwrap());
+    assert!(equivalent("{-3..3}", "{-{1..3},{0..3}}", &config).unwrap());
+}
+
+#[test]
+fn test_equivalent_with_case_folding() {
+    let folded = BraceConfig {
+        case_folding: CaseFolding::FirstSeen,
+        ..Default::default()
+    };
+    assert!(equivalent(r"C:\{Src,SRC}\{a,B}.rs", r"c:\src\{A,b}.RS", &folded).unwrap());
+    assert!(equivalent("{A..C}", "{a,b,c}", &folded).unwrap());
+    assert!(!equivalent("{A..C}", "{a,b}", &folded).unwrap());
+
+    let config = BraceConfig::default();
+    assert!(!equivalent("{A..C}", "{a,b,c}", &config).unwrap());
+}
@@ -0,0 +1,60 @@
+use braces::{brace_paths, BraceConfig, CaseFolding, VerifyMode};
+
+fn folding(case_folding: CaseFolding) -> BraceConfig {
+    BraceConfig {
+        case_folding,
+        verify: VerifyMode::Error,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_case_sensitive_by_default() {
+    let result = brace_paths(&["Src/Lib.rs", "src/lib.rs"], &BraceConfig::default()).unwrap();
+    assert_eq!(result, "{Src/L,src/l}ib.rs");
+}
+
+#[test]
+fn test_fold_first_seen() {
+    let paths = vec!["Src/Lib.rs", "src/lib.rs", "src/main.rs"];
+    let result = brace_paths(&paths, &folding(CaseFolding::FirstSeen)).unwrap();
+    assert_eq!(result, "Src/{Lib,main}.rs");
+}
+
+#[test]
+fn test_fold_most_common() {
+    let paths = vec!["SRC/a.rs", "src/b.rs", "src/c.rs", "Src/d.rs"];
+    let result = brace_paths(&paths, &folding(CaseFolding::MostCommon)).unwrap();
+    assert_eq!(result, "src/{a,b,c,d}.rs");
+}
+
+#[test]
+fn test_fold_most_common_tie_uses_first_seen() {
+    let paths = vec!["Docs/a", "docs/b"];
+    let result = brace_paths(&paths, &folding(CaseFolding::MostCommon)).unwrap();
+    assert_eq!(result, "Docs/{a,b}");
+}
+
+#[test]
+fn test_fold_lowercase() {
+    let paths = vec!["Src/Lib.rs", "SRC/Main.rs"];
+    let result = brace_paths(&paths, &folding(CaseFolding::Lowercase)).unwrap();
+    assert_eq!(result, "src/{lib,main}.rs");
+}
+
+#[test]
+fn test_fold_deduplicates_only_when_enabled() {
+    let config = BraceConfig {
+        case_folding: CaseFolding::FirstSeen,
+        deduplicate_inputs: false,
+        ..Default::default()
+    };
+    let paths = vec!["README.md", "readme.md"];
+    assert_eq!(brace_paths(&paths, &config).unwrap(), "{README,readme}.md");
+
+    let config = BraceConfig {
+        deduplicate_inputs: true,
+        ..config
+    };
+    assert_eq!(brace_paths(&paths, &config).unwrap(), "README.md");
+}
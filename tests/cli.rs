@@ -104,3 +104,33 @@ fn test_triple_nesting() {
     let output = highlight_braces("{a/{b/{c,d},e},f}");
     insta::assert_snapshot!(output);
 }
+
+#[test]
+fn test_conflicting_modes_rejected() {
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_braces"))
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let cases: [&[&str]; 4] = [
+        &["--regex", "--budget", "10", "a/b"],
+        &["--diff", "a", "--zoom", "1", "a/b"],
+        &["--union", "x", "--intersect", "y", "a/b"],
+        &["--hint", "a/{b,c}", "--regex", "a/b"],
+    ];
+    for args in cases {
+        let output = run(args);
+        assert!(!output.status.success(), "{:?}", args);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains("cannot be combined"),
+            "{:?}: {}",
+            args,
+            stderr
+        );
+    }
+
+    let output = run(&["--regex", "--regex", "a/b", "a/c"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "^a/(?:b|c)$\n");
+}
@@ -0,0 +1,66 @@
+use braces::{diff_paths, BraceConfig, BraceError, Direction};
+
+#[test]
+fn test_separate_and_annotated_forms() {
+    let before = vec!["src/a.rs", "src/b.rs", "src/c.rs"];
+    let after = vec!["src/b.rs", "src/c.rs", "src/d.rs"];
+    let diff = diff_paths(&before, &after, &BraceConfig::default()).unwrap();
+    assert_eq!(diff.added.as_deref(), Some("src/d.rs"));
+    assert_eq!(diff.removed.as_deref(), Some("src/a.rs"));
+    assert_eq!(diff.unchanged.as_deref(), Some("src/{b,c}.rs"));
+    assert_eq!(diff.annotated.as_deref(), Some("src/{-a,+d}.rs"));
+    assert_eq!(diff.to_string(), "+ src/d.rs / - src/a.rs");
+}
+
+#[test]
+fn test_no_changes() {
+    let paths = vec!["a/x", "a/y"];
+    let diff = diff_paths(&paths, &paths, &BraceConfig::default()).unwrap();
+    assert!(diff.is_empty());
+    assert_eq!(diff.annotated, None);
+    assert_eq!(diff.unchanged.as_deref(), Some("a/{x,y}"));
+    assert_eq!(diff.to_string(), "");
+}
+
+#[test]
+fn test_only_additions() {
+    let before: Vec<&str> = vec![];
+    let diff = diff_paths(&before, &["a", "b"], &BraceConfig::default()).unwrap();
+    assert_eq!(diff.removed, None);
+    assert_eq!(diff.annotated.as_deref(), Some("{+a,+b}"));
+    assert_eq!(diff.to_string(), "+ {a,b}");
+}
+
+#[test]
+fn test_marks_last_segment() {
+    let diff = diff_paths(&["-rf/x", "dir/"], &["-rf/y"], &BraceConfig::default()).unwrap();
+    assert_eq!(diff.annotated.as_deref(), Some("{-rf/{-x,+y},-dir/}"));
+}
+
+#[test]
+fn test_right_to_left_marks_whole_path() {
+    let config = BraceConfig {
+        path_separator: ".".to_string(),
+        direction: Direction::RightToLeft,
+        ..Default::default()
+    };
+    let diff = diff_paths(&["api.example.com"], &["web.example.com"], &config).unwrap();
+    assert_eq!(diff.annotated.as_deref(), Some("{-api,+web}.example.com"));
+}
+
+#[test]
+fn test_rejects_marker_characters() {
+    let result = diff_paths(&["a/\u{E000}x"], &["a/y"], &BraceConfig::default());
+    assert!(matches!(result, Err(BraceError::InvalidBraceInput { .. })));
+}
+
+#[cfg(all(feature = "cli", feature = "highlight"))]
+#[test]
+fn test_highlighted_marks() {
+    let config = BraceConfig {
+        highlight: true,
+        ..Default::default()
+    };
+    let diff = diff_paths(&["src/a.rs"], &["src/b.rs"], &config).unwrap();
+    insta::assert_snapshot!(diff.annotated.unwrap());
+}
@@ -0,0 +1,68 @@
+use braces::{brace_paths, BraceConfig, Direction, VerifyMode};
+
+fn dotted(direction: Direction) -> BraceConfig {
+    BraceConfig {
+        path_separator: ".".to_string(),
+        direction,
+        verify: VerifyMode::Error,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_host_names_right_to_left() {
+    let hosts = vec!["api.prod.example.com", "web.prod.example.com"];
+    let result = brace_paths(&hosts, &dotted(Direction::RightToLeft)).unwrap();
+    assert_eq!(result, "{api,web}.prod.example.com");
+}
+
+#[test]
+fn test_nested_host_names() {
+    let hosts = vec![
+        "api.prod.example.com",
+        "web.prod.example.com",
+        "api.staging.example.com",
+        "example.org",
+    ];
+    let result = brace_paths(&hosts, &dotted(Direction::RightToLeft)).unwrap();
+    assert_eq!(
+        result,
+        "{{{api,web}.prod,api.staging}.example.com,example.org}"
+    );
+}
+
+#[test]
+fn test_reverse_dns_left_to_right() {
+    let packages = vec!["com.example.app.ui", "com.example.app.data"];
+    let result = brace_paths(&packages, &dotted(Direction::LeftToRight)).unwrap();
+    assert_eq!(result, "com.example.app.{ui,data}");
+}
+
+#[test]
+fn test_shared_prefix_factored_right_to_left() {
+    let hosts = vec!["www.example.com", "www.example.org"];
+    let result = brace_paths(&hosts, &dotted(Direction::RightToLeft)).unwrap();
+    assert_eq!(result, "www.example.{com,org}");
+}
+
+#[test]
+fn test_sort_from_the_right() {
+    let config = BraceConfig {
+        sort_items: true,
+        ..dotted(Direction::RightToLeft)
+    };
+    let hosts = vec!["a.zeta.net", "b.alpha.net", "c.mid.net"];
+    let result = brace_paths(&hosts, &config).unwrap();
+    assert_eq!(result, "{b.alpha,c.mid,a.zeta}.net");
+}
+
+#[test]
+fn test_stem_split_right_to_left() {
+    let config = BraceConfig {
+        allow_stem_split: true,
+        ..dotted(Direction::RightToLeft)
+    };
+    let hosts = vec!["api-eu.example.com", "api-us.example.com"];
+    let result = brace_paths(&hosts, &config).unwrap();
+    assert_eq!(result, "api-{eu,us}.example.com");
+}
@@ -1,4 +1,4 @@
-use braces::{brace_paths, BraceConfig, BraceError};
+use braces::{brace_paths, BraceConfig, BraceError, ParseError, ParseErrorKind};
 
 #[test]
 fn test_error_display() {
@@ -19,6 +19,29 @@ fn test_error_display() {
 
     let depth_err = BraceError::DepthLimitExceeded { limit: 5 };
     assert!(format!("{}", depth_err).contains("depth limit"));
+
+    let verify_err = BraceError::VerificationFailed {
+        missing: vec!["a/".to_string()],
+        unexpected: vec!["a".to_string()],
+    };
+    assert!(format!("{}", verify_err).contains("does not expand to the input"));
+
+    let base_err = BraceError::OutsideBase {
+        path: "/etc/passwd".to_string(),
+        base: "/repo".to_string(),
+    };
+    assert!(format!("{}", base_err).contains("outside the base directory"));
+
+    let syntax_err = BraceError::InvalidBraceSyntax {
+        errors: vec![ParseError {
+            kind: ParseErrorKind::UnclosedGroup,
+            span: 2..3,
+        }],
+    };
+    assert_eq!(
+        format!("{}", syntax_err),
+        "Invalid brace syntax: unclosed '{' at 2..3"
+    );
 }
 
 #[test]
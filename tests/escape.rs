@@ -0,0 +1,77 @@
+use braces::{brace_paths, expand_braces_with, BraceConfig, BraceError, EscapePolicy};
+
+fn with_policy(escape: EscapePolicy) -> BraceConfig {
+    BraceConfig {
+        escape,
+        ..Default::default()
+    }
+}
+
+fn assert_escaped_round_trip(paths: Vec<&str>, config: &BraceConfig) {
+    let result = brace_paths(&paths, config).unwrap();
+    let mut expanded = expand_braces_with(&result, config.escape);
+    expanded.sort();
+    let mut expected: Vec<String> = paths.iter().map(|p| p.to_string()).collect();
+    expected.sort();
+    assert_eq!(expanded, expected, "Round trip of '{}'", result);
+}
+
+#[test]
+fn test_comma_rejected_by_default() {
+    let result = brace_paths(&["a,b.txt", "c.txt"], &BraceConfig::default());
+    assert!(matches!(result, Err(BraceError::InvalidBraceInput { .. })));
+}
+
+#[test]
+fn test_backslash_escaping() {
+    let config = with_policy(EscapePolicy::Backslash);
+    let result = brace_paths(&["a,b.txt", "c.txt"], &config).unwrap();
+    assert_eq!(result, r"{a\,b,c}.txt");
+
+    let result = brace_paths(&["x/{y}", "x/z"], &config).unwrap();
+    assert_eq!(result, r"x/{\{y\},z}");
+}
+
+#[test]
+fn test_quote_escaping() {
+    let config = with_policy(EscapePolicy::Quote);
+    let result = brace_paths(&["a,b.txt", "c.txt"], &config).unwrap();
+    assert_eq!(result, "{'a,b',c}.txt");
+
+    let result = brace_paths(&["it's,here", "x"], &config).unwrap();
+    assert_eq!(result, r"{'it'\''s,here',x}");
+}
+
+#[test]
+fn test_round_trip_backslash() {
+    let config = with_policy(EscapePolicy::Backslash);
+    assert_escaped_round_trip(vec!["a,b/c.rs", "a,b/{d}.rs", "e}/f.rs", "g'h.rs"], &config);
+}
+
+#[test]
+fn test_round_trip_quote() {
+    let config = with_policy(EscapePolicy::Quote);
+    assert_escaped_round_trip(vec!["a,b/c.rs", "a,b/{d}.rs", "e}/f.rs", "g'h.rs"], &config);
+}
+
+#[test]
+fn test_stem_split_keeps_escapes_whole() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        allow_stem_split: true,
+        ..Default::default()
+    };
+    assert_escaped_round_trip(vec!["x/a,1", "x/a,2", "x/a}"], &config);
+    assert_escaped_round_trip(vec!["x/a{1", "x/a{2"], &config);
+}
+
+#[test]
+fn test_reprocess_with_escapes() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        reprocess_braces: true,
+        ..Default::default()
+    };
+    let result = brace_paths(&[r"a/{b,c\,d}"], &config).unwrap();
+    assert_eq!(result, r"a/{b,c\,d}");
+}
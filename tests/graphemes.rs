@@ -0,0 +1,46 @@
+#![cfg(feature = "graphemes")]
+
+use braces::{brace_paths_with, BraceConfig, GraphemeSegmenter, Segmenter};
+
+#[test]
+fn test_grapheme_segments() {
+    let segments = |text| GraphemeSegmenter.segments(text);
+    assert_eq!(segments("cafe\u{301}"), vec!["c", "a", "f", "e\u{301}"]);
+    // A flag is two regional indicators
+    assert_eq!(
+        segments("\u{1F1EC}\u{1F1E7}\u{1F1EB}\u{1F1F7}"),
+        vec!["\u{1F1EC}\u{1F1E7}", "\u{1F1EB}\u{1F1F7}"]
+    );
+    // A family emoji is joined by zero-width joiners
+    assert_eq!(
+        segments("a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}b"),
+        vec!["a", "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}", "b"]
+    );
+    assert_eq!(segments("x\r\ny"), vec!["x", "\r\n", "y"]);
+    assert_eq!(segments(""), vec![""]);
+}
+
+#[test]
+fn test_grapheme_segmenter_keeps_marks() {
+    let words = vec!["cafe\u{301}s", "cafe\u{301}"];
+    let config = BraceConfig {
+        allow_stem_split: true,
+        ..Default::default()
+    };
+    let result = brace_paths_with(&words, &config, &GraphemeSegmenter).unwrap();
+    assert_eq!(result, "cafe\u{301}{s,}");
+}
+
+#[test]
+fn test_grapheme_segmenter_keeps_indic_clusters() {
+    let words = vec!["கொa", "கொb", "கௌ"];
+    let result = brace_paths_with(&words, &BraceConfig::default(), &GraphemeSegmenter).unwrap();
+    assert_eq!(result, "{கொ{a,b},கௌ}");
+}
+
+#[test]
+fn test_grapheme_segments_hangul_jamo() {
+    // A syllable spelled as leading, vowel and trailing jamo is one character
+    let segments = GraphemeSegmenter.segments("\u{1100}\u{1161}\u{11A8}x");
+    assert_eq!(segments, vec!["\u{1100}\u{1161}\u{11A8}", "x"]);
+}
@@ -1,6 +1,6 @@
 #![cfg(feature = "highlight")]
 
-use braces::{brace_paths, pretty_braces, BraceConfig};
+use braces::{brace_paths, pretty_braces, BraceConfig, EscapePolicy, PathStyle};
 
 #[test]
 fn config_highlight_applies_to_output() {
@@ -147,3 +147,55 @@ fn config_highlight_with_empty_braces() {
 
     insta::assert_snapshot!(result);
 }
+
+#[test]
+#[cfg(feature = "cli")]
+fn config_highlight_windows_escaped() {
+    let config = BraceConfig {
+        highlight: true,
+        path_style: PathStyle::Windows,
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let result = brace_paths(&["C:\\x\\a,b", "C:\\x\\c"], &config).unwrap();
+
+    // Read with the resolved quoting, the group braces are coloured
+    assert!(result.contains("\x1b["), "not highlighted: {:?}", result);
+    let plain = regex::Regex::new("\x1b\\[[0-9;]*m")
+        .unwrap()
+        .replace_all(&result, "");
+    assert_eq!(plain, "C:\\x\\{'a,b',c}");
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn ansi_renderer_matches_highlighted_text() {
+    use braces::brace_paths_expr;
+    use braces::highlight::{highlight_braces_with, AnsiRenderer};
+
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let paths = [
+        "src/{x}/a.rs",
+        "src/b.rs",
+        "src/c/d.rs",
+        "src/c/e.rs",
+        "lib",
+    ];
+    let expr = brace_paths_expr(&paths, &config).unwrap();
+    assert_eq!(
+        expr.render(&AnsiRenderer, config.escape),
+        highlight_braces_with(&expr.to_string(), config.escape)
+    );
+
+    let highlighted = BraceConfig {
+        highlight: true,
+        ..config
+    };
+    assert_eq!(
+        brace_paths(&paths, &highlighted).unwrap(),
+        expr.render(&AnsiRenderer, EscapePolicy::Backslash)
+    );
+}
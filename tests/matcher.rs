@@ -0,0 +1,153 @@
+use braces::{
+    expand_braces, BraceConfig, BraceError, BraceMatcher, CaseFolding, EscapePolicy, GroupChoice,
+    PathStyle,
+};
+
+#[test]
+fn test_matches_exactly_the_expansion() {
+    let expr = "src/{a,b/{c..e},{08..11}}/{x,}.rs";
+    let matcher = BraceMatcher::new(expr, &BraceConfig::default()).unwrap();
+    for path in expand_braces(expr) {
+        assert!(matcher.is_match(&path), "{} should match", path);
+    }
+    for path in [
+        "src/a/y.rs",
+        "src/a/x",
+        "src/b/f/x.rs",
+        "src/8/x.rs",
+        "src/12/x.rs",
+        "src/a/x.rs/",
+        "",
+    ] {
+        assert!(!matcher.is_match(path), "{} should not match", path);
+    }
+}
+
+#[test]
+fn test_large_sequences_are_not_expanded() {
+    let matcher = BraceMatcher::new("log/{1..1000000000}.txt", &BraceConfig::default()).unwrap();
+    assert!(matcher.is_match("log/999999999.txt"));
+    assert!(matcher.is_match("log/1.txt"));
+    assert!(!matcher.is_match("log/0.txt"));
+    assert!(!matcher.is_match("log/1000000001.txt"));
+
+    let negative = BraceMatcher::new("{-15..-3}", &BraceConfig::default()).unwrap();
+    assert!(negative.is_match("-15"));
+    assert!(negative.is_match("-3"));
+    assert!(!negative.is_match("-2"));
+    assert!(!negative.is_match("3"));
+}
+
+#[test]
+fn test_stepped_sequences() {
+    let matcher = BraceMatcher::new("{0..20..5}", &BraceConfig::default()).unwrap();
+    assert!(matcher.is_match("15"));
+    assert!(!matcher.is_match("16"));
+}
+
+#[test]
+fn test_reports_choices() {
+    let matcher = BraceMatcher::new("{a,b}/{x,{1..9},y}", &BraceConfig::default()).unwrap();
+    let found = matcher.find("b/7").unwrap();
+    assert_eq!(
+        found.choices,
+        vec![
+            GroupChoice {
+                group: 0..5,
+                alternative: 1,
+                matched: 0..1,
+            },
+            GroupChoice {
+                group: 6..18,
+                alternative: 1,
+                matched: 2..3,
+            },
+            GroupChoice {
+                group: 9..15,
+                alternative: 6,
+                matched: 2..3,
+            },
+        ]
+    );
+    assert_eq!(matcher.find("c/7"), None);
+}
+
+#[test]
+fn test_prefers_earliest_alternative() {
+    let matcher = BraceMatcher::new("{a,ab}{b,}", &BraceConfig::default()).unwrap();
+    let found = matcher.find("ab").unwrap();
+    let taken: Vec<_> = found.choices.iter().map(|c| c.alternative).collect();
+    assert_eq!(taken, vec![0, 0]);
+}
+
+#[test]
+fn test_escapes() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let matcher = BraceMatcher::new(r"a\{b\,c\}/{d,e}", &config).unwrap();
+    assert!(matcher.is_match("a{b,c}/e"));
+    assert!(!matcher.is_match("ab/e"));
+
+    let glob = BraceMatcher::glob(r"a/\*/*", &config).unwrap();
+    assert!(glob.is_match("a/*/x"));
+    assert!(!glob.is_match("a/b/x"));
+}
+
+#[test]
+fn test_wildcards_only_in_glob() {
+    let config = BraceConfig::default();
+    assert!(BraceMatcher::new("a/*.rs", &config)
+        .unwrap()
+        .is_match("a/*.rs"));
+    assert!(!BraceMatcher::new("a/*.rs", &config)
+        .unwrap()
+        .is_match("a/b.rs"));
+
+    let glob = BraceMatcher::glob("src/**/{mod,lib}.r?", &config).unwrap();
+    assert!(glob.is_match("src/lib.rs"));
+    assert!(glob.is_match("src/a/b/mod.rs"));
+    assert!(!glob.is_match("src/a/b/mod.r/"));
+    assert!(!glob.is_match("src/main.rs"));
+
+    let star = BraceMatcher::glob("{src,tests}/*", &config).unwrap();
+    assert!(star.is_match("tests/"));
+    assert!(!star.is_match("tests/a/b"));
+}
+
+#[test]
+fn test_case_folding() {
+    let config = BraceConfig {
+        path_style: PathStyle::Windows,
+        ..Default::default()
+    };
+    let matcher = BraceMatcher::new(r"C:\Src\{a..c}.rs", &config).unwrap();
+    assert!(matcher.is_match(r"c:\src\B.RS"));
+    assert!(!BraceMatcher::new("Src/a", &BraceConfig::default())
+        .unwrap()
+        .is_match("src/a"));
+}
+
+#[test]
+fn test_case_folding_uppercase_ranges() {
+    let config = BraceConfig {
+        case_folding: CaseFolding::Lowercase,
+        ..Default::default()
+    };
+    let upper = BraceMatcher::new("{A..C}", &config).unwrap();
+    assert!(upper.is_match("b"));
+    assert!(upper.is_match("B"));
+    assert!(!upper.is_match("d"));
+    let lower = BraceMatcher::new("{a..c}", &config).unwrap();
+    assert!(lower.is_match("B"));
+    assert!(!BraceMatcher::new("{A..C}", &BraceConfig::default())
+        .unwrap()
+        .is_match("b"));
+}
+
+#[test]
+fn test_invalid_expression() {
+    let result = BraceMatcher::new("a/{b,c", &BraceConfig::default());
+    assert!(matches!(result, Err(BraceError::InvalidBraceSyntax { .. })));
+}
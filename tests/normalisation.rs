@@ -0,0 +1,99 @@
+use braces::{brace_paths, BraceConfig, LeadingDot, Normalisation, VerifyMode};
+
+fn normalising(leading_dot: LeadingDot) -> BraceConfig {
+    BraceConfig {
+        normalisation: Normalisation {
+            collapse_separators: true,
+            remove_dot_segments: true,
+            resolve_parent_segments: true,
+            leading_dot,
+        },
+        verify: VerifyMode::Error,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_no_normalisation_by_default() {
+    let result = brace_paths(&["./a.rs", "b.rs"], &BraceConfig::default()).unwrap();
+    assert_eq!(result, "{./a,b}.rs");
+}
+
+#[test]
+fn test_equivalent_spellings_merge() {
+    let paths = vec!["./src/a.rs", "src//a.rs", "src/./a.rs", "src/x/../a.rs"];
+    let result = brace_paths(&paths, &normalising(LeadingDot::Strip)).unwrap();
+    assert_eq!(result, "src/a.rs");
+}
+
+#[test]
+fn test_steps_are_independent() {
+    let config = BraceConfig {
+        normalisation: Normalisation {
+            collapse_separators: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = brace_paths(&["src//a.rs", "src/./b.rs"], &config).unwrap();
+    assert_eq!(result, "src/{a,./b}.rs");
+}
+
+#[test]
+fn test_parent_segments_resolved_only_when_safe() {
+    let paths = vec!["../lib/a.rs", "src/../../c.rs", "src/./../d.rs"];
+    let result = brace_paths(&paths, &normalising(LeadingDot::Keep)).unwrap();
+    assert_eq!(result, "{../{lib/a,c},d}.rs");
+}
+
+#[test]
+fn test_trailing_separator_preserved() {
+    let paths = vec!["src/y/.", "src/z//", "src/w/x/.."];
+    let result = brace_paths(&paths, &normalising(LeadingDot::Keep)).unwrap();
+    assert_eq!(result, "src/{y,z,w}/");
+}
+
+#[test]
+fn test_leading_dot_keep() {
+    let paths = vec!["./a.rs", "b.rs"];
+    let result = brace_paths(&paths, &normalising(LeadingDot::Keep)).unwrap();
+    assert_eq!(result, "{./a,b}.rs");
+}
+
+#[test]
+fn test_leading_dot_enforce() {
+    let paths = vec!["./a.rs", "b.rs", "lib//c.rs"];
+    let result = brace_paths(&paths, &normalising(LeadingDot::Enforce)).unwrap();
+    assert_eq!(result, "./{a,b,lib/c}.rs");
+}
+
+#[test]
+fn test_leading_dot_strip_keeps_bare_dot() {
+    let paths = vec!["./a.rs", ".", "./x/.."];
+    let result = brace_paths(&paths, &normalising(LeadingDot::Strip)).unwrap();
+    assert_eq!(result, "{a.rs,.}");
+}
+
+#[test]
+fn test_leading_dot_enforce_skips_parent_paths() {
+    let paths = vec!["../a.rs", "b.rs"];
+    let result = brace_paths(&paths, &normalising(LeadingDot::Enforce)).unwrap();
+    assert_eq!(result, "{../a,./b}.rs");
+}
+
+#[test]
+fn test_empty_path_is_not_a_root() {
+    let config = BraceConfig {
+        normalisation: Normalisation {
+            collapse_separators: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let normalised = brace_paths(&["", "a"], &config).unwrap();
+    assert_eq!(
+        normalised,
+        brace_paths(&["", "a"], &BraceConfig::default()).unwrap()
+    );
+    assert!(!normalised.contains('/'));
+}
@@ -0,0 +1,173 @@
+use braces::{
+    brace_paths, expand_braces, parse_braces, pretty_braces_with, BraceConfig, BraceError,
+    BraceExpr, BraceRange, EscapePolicy, ParseErrorKind, SpannedKind,
+};
+
+#[test]
+fn test_spans_of_nodes() {
+    let parsed = parse_braces("a/{b,cd}.rs", EscapePolicy::Reject);
+    assert!(parsed.errors.is_empty());
+    assert_eq!(parsed.expr.span, 0..11);
+
+    let SpannedKind::Concat(parts) = &parsed.expr.kind else {
+        panic!("root is not a sequence");
+    };
+    let spans: Vec<_> = parts.iter().map(|p| p.span.clone()).collect();
+    assert_eq!(spans, vec![0..2, 2..8, 8..11]);
+
+    let SpannedKind::Alt(options) = &parts[1].kind else {
+        panic!("expected a group");
+    };
+    let spans: Vec<_> = options.iter().map(|o| o.span.clone()).collect();
+    assert_eq!(spans, vec![3..4, 5..7]);
+}
+
+#[test]
+fn test_spans_are_bytes() {
+    let parsed = parse_braces("é/{ü,x", EscapePolicy::Reject);
+    assert_eq!(parsed.errors.len(), 1);
+    assert_eq!(parsed.errors[0].kind, ParseErrorKind::UnclosedGroup);
+    assert_eq!(parsed.errors[0].span, 3..4);
+    assert_eq!(parsed.expr.span, 0..8);
+}
+
+#[test]
+fn test_reports_every_error() {
+    let parsed = parse_braces(r"}a/{b,'c\", EscapePolicy::Backslash);
+    let kinds: Vec<_> = parsed.errors.iter().map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ParseErrorKind::UnmatchedClose,
+            ParseErrorKind::UnclosedGroup,
+            ParseErrorKind::UnterminatedQuote,
+        ]
+    );
+    assert_eq!(parsed.errors[2].span, 6..9);
+    assert_eq!(parsed.expr.expand(), vec![r"}a/{b,c\"]);
+
+    let parsed = parse_braces(r"a\", EscapePolicy::Backslash);
+    assert_eq!(parsed.errors[0].kind, ParseErrorKind::TrailingEscape);
+    assert_eq!(parsed.expr.expand(), vec![r"a\"]);
+}
+
+#[test]
+fn test_escapes_follow_policy() {
+    let text = r"{a\,b,c}";
+    let backslash = parse_braces(text, EscapePolicy::Backslash);
+    assert_eq!(backslash.expr.expand(), vec!["a,b", "c"]);
+
+    let reject = parse_braces(text, EscapePolicy::Reject);
+    assert_eq!(reject.expr.expand(), vec![r"a\", "b", "c"]);
+
+    let doubled = parse_braces("{'it''s,x',y}", EscapePolicy::QuoteDoubled);
+    assert_eq!(doubled.expr.expand(), vec!["it's,x", "y"]);
+}
+
+#[test]
+fn test_ansi_is_expanded_exactly() {
+    assert_eq!(
+        expand_braces("\x1b[31mred\x1b[0m"),
+        vec!["\x1b[31mred\x1b[0m"]
+    );
+    assert_eq!(
+        expand_braces("x\x1b[1m/{a,b}"),
+        vec!["x\x1b[1m/a", "x\x1b[1m/b"]
+    );
+    let parsed = parse_braces("{a,\x1b[1mb}", EscapePolicy::Reject);
+    assert!(parsed.errors.is_empty());
+    assert_eq!(parsed.expr.expand(), vec!["a", "\x1b[1mb"]);
+}
+
+#[test]
+fn test_pretty_ignores_ansi() {
+    let pretty = pretty_braces_with("\x1b[36m{\x1b[0ma,b\x1b[36m}\x1b[0m", EscapePolicy::Reject);
+    assert_eq!(pretty, "\x1b[36m{\n \x1b[0ma,\n b\x1b[36m\n}");
+}
+
+#[test]
+fn test_parse_round_trips_output() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let paths = vec!["src/a,b.rs", "src/c.rs", "tests/d.rs"];
+    let text = brace_paths(&paths, &config).unwrap();
+    let expr = BraceExpr::parse(&text, config.escape).unwrap();
+    assert_eq!(expr.to_string(), text);
+    assert_eq!(expr.expansion_count(), paths.len());
+}
+
+#[test]
+fn test_parse_rejects_invalid_syntax() {
+    let err = BraceExpr::parse("{a,{b}", EscapePolicy::Reject).unwrap_err();
+    let BraceError::InvalidBraceSyntax { errors } = err else {
+        panic!("unexpected error {:?}", err);
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].span, 0..1);
+}
+
+#[test]
+fn test_pretty_keeps_escaped_commas() {
+    let pretty = pretty_braces_with(r"x/{a\,b,c}", EscapePolicy::Backslash);
+    assert_eq!(pretty, "x/{\n   a\\,b,\n   c\n  }");
+}
+
+#[test]
+fn test_numeric_sequences() {
+    assert_eq!(expand_braces("f{1..4}"), vec!["f1", "f2", "f3", "f4"]);
+    assert_eq!(expand_braces("{3..1}"), vec!["3", "2", "1"]);
+    assert_eq!(expand_braces("{-1..1}"), vec!["-1", "0", "1"]);
+    assert_eq!(expand_braces("{1..10..-4}"), vec!["1", "5", "9"]);
+    assert_eq!(expand_braces("{08..10}"), vec!["08", "09", "10"]);
+    assert_eq!(expand_braces("{-2..01}"), vec!["-2", "-1", "00", "01"]);
+}
+
+#[test]
+fn test_letter_sequences() {
+    assert_eq!(expand_braces("{a..c}.rs"), vec!["a.rs", "b.rs", "c.rs"]);
+    assert_eq!(expand_braces("{E..A..2}"), vec!["E", "C", "A"]);
+    // Mixed case, mixed kinds and other text are not sequences
+    assert_eq!(expand_braces("{a..C}"), vec!["a..C"]);
+    assert_eq!(expand_braces("{1..c}"), vec!["1..c"]);
+    assert_eq!(expand_braces("{1...3}"), vec!["1...3"]);
+}
+
+#[test]
+fn test_sequence_nodes() {
+    let parsed = parse_braces("x{1..3}{a,{c..d}}", EscapePolicy::Reject);
+    assert!(parsed.errors.is_empty());
+    let SpannedKind::Concat(parts) = &parsed.expr.kind else {
+        panic!("root is not a sequence");
+    };
+    assert_eq!(parts[1].span, 1..7);
+    assert_eq!(
+        parts[1].kind,
+        SpannedKind::Range(BraceRange::Numeric {
+            start: 1,
+            end: 3,
+            step: 1,
+            width: 0
+        })
+    );
+    assert_eq!(parsed.expr.expand().len(), 9);
+
+    let expr = BraceExpr::parse("f{1..3}", EscapePolicy::Reject).unwrap();
+    assert_eq!(expr.to_string(), "f{1,2,3}");
+}
+
+#[test]
+fn test_sequence_positions() {
+    let range = BraceRange::Numeric {
+        start: 10,
+        end: -2,
+        step: 4,
+        width: 3,
+    };
+    let items: Vec<_> = range.items().collect();
+    assert_eq!(items, vec!["010", "006", "002", "-02"]);
+    assert_eq!(range.position("-02"), Some(3));
+    assert_eq!(range.position("6"), None);
+    assert_eq!(range.position("-06"), None);
+}
@@ -0,0 +1,220 @@
+use braces::{
+    brace_paths_expr, brace_paths_regex, expand_braces, BraceConfig, BraceExpr, CaseFolding,
+    Direction, EscapePolicy, PathStyle,
+};
+use regex::Regex;
+
+/// Check that the regex for `paths` matches each of them and none of `others`
+fn assert_exact(paths: &[&str], others: &[&str], config: &BraceConfig) -> String {
+    let pattern = brace_paths_regex(paths, config).unwrap();
+    let regex = Regex::new(&pattern).unwrap();
+    for path in paths {
+        assert!(regex.is_match(path), "{} should match {}", pattern, path);
+    }
+    for path in others {
+        assert!(
+            !regex.is_match(path),
+            "{} should not match {}",
+            pattern,
+            path
+        );
+    }
+    pattern
+}
+
+#[test]
+fn test_groups_and_literals() {
+    let pattern = assert_exact(
+        &["src/a.rs", "src/b.rs", "lib/c+d.rs"],
+        &[
+            "src/a.rs/",
+            "xsrc/a.rs",
+            "src/ab.rs",
+            "src/a-rs",
+            "lib/cd.rs",
+        ],
+        &BraceConfig::default(),
+    );
+    assert_eq!(pattern, r"^(?:src/(?:a|b)|lib/c\+d)\.rs$");
+}
+
+#[test]
+fn test_empty_alternatives_are_optional() {
+    let expr = BraceExpr::parse("a/{b.rs{,.bak},c}", EscapePolicy::Reject).unwrap();
+    assert_eq!(
+        expr.to_regex(EscapePolicy::Reject),
+        r"^a/(?:b\.rs(?:\.bak)?|c)$"
+    );
+}
+
+#[test]
+fn test_prefix_paths_match_exactly() {
+    // Braces output writes `a/b/{c,}`, whose expansion is `a/b/`
+    let pattern = assert_exact(
+        &["a/b", "a/b/c", "a/d"],
+        &["a/b/", "a/"],
+        &BraceConfig::default(),
+    );
+    assert_eq!(pattern, "^a/(?:b(?:/c)?|d)$");
+
+    let pattern = assert_exact(
+        &["a", "a/b", "a/c"],
+        &["a/", "a/d"],
+        &BraceConfig::default(),
+    );
+    assert_eq!(pattern, "^a(?:/(?:b|c))?$");
+}
+
+#[test]
+fn test_trailing_separators_kept() {
+    let pattern = assert_exact(
+        &["a/b/", "a/b/c", "x/y", "x/y/z"],
+        &["a/b", "x/y/"],
+        &BraceConfig::default(),
+    );
+    assert_eq!(pattern, "^(?:a/b/(?:c)?|x/y(?:/z)?)$");
+}
+
+#[test]
+fn test_shared_segments_factored() {
+    let pattern = assert_exact(
+        &["x/y/a.rs", "x/y/b.rs"],
+        &["x/a.rs", "x/y/c.rs"],
+        &BraceConfig::default(),
+    );
+    assert_eq!(pattern, r"^x/y/(?:a|b)\.rs$");
+
+    let config = BraceConfig {
+        path_style: PathStyle::Windows,
+        ..Default::default()
+    };
+    let pattern = assert_exact(&[r"C:\Users\x\a", r"C:\Users\x\b"], &[], &config);
+    assert_eq!(pattern, r"(?i)^C:\\Users\\x\\(?:a|b)$");
+
+    let config = BraceConfig {
+        path_style: PathStyle::Url,
+        ..Default::default()
+    };
+    let pattern = assert_exact(
+        &[
+            "https://x.io/docs",
+            "https://x.io/docs/a",
+            "https://x.io/docs/b",
+        ],
+        &["https://x.io/docs/", "https://x.io/a"],
+        &config,
+    );
+    assert_eq!(pattern, r"^https://x\.io/docs(?:/a|/b)?$");
+}
+
+#[test]
+fn test_split_leaves_right_to_left() {
+    let config = BraceConfig {
+        path_separator: ".".into(),
+        direction: Direction::RightToLeft,
+        ..Default::default()
+    };
+    let pattern = assert_exact(
+        &["example.com", "api.example.com", "web.example.com"],
+        &[".example.com", "ftp.example.com"],
+        &config,
+    );
+    assert_eq!(pattern, r"^(?:(?:api|web)\.)?example\.com$");
+}
+
+#[test]
+fn test_numeric_ranges_become_classes() {
+    let paths: Vec<String> = (1..=24).map(|i| format!("log/{}.txt", i)).collect();
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let pattern = assert_exact(
+        &paths,
+        &["log/0.txt", "log/25.txt", "log/01.txt", "log/100.txt"],
+        &BraceConfig::default(),
+    );
+    assert_eq!(pattern, r"^log/(?:[1-9]|1[0-9]|2[0-4])\.txt$");
+}
+
+#[test]
+fn test_padded_and_partial_ranges() {
+    let expr = BraceExpr::parse("f{08,09,10,11}", EscapePolicy::Reject).unwrap();
+    assert_eq!(expr.to_regex(EscapePolicy::Reject), "^f(?:0[89]|1[01])$");
+
+    // A gap, or mixed padding, keeps the alternatives as they are
+    let expr = BraceExpr::parse("f{1,2,4}", EscapePolicy::Reject).unwrap();
+    assert_eq!(expr.to_regex(EscapePolicy::Reject), "^f(?:1|2|4)$");
+    let expr = BraceExpr::parse("f{1,02,3}", EscapePolicy::Reject).unwrap();
+    assert_eq!(expr.to_regex(EscapePolicy::Reject), "^f(?:1|02|3)$");
+
+    let expr = BraceExpr::parse("f{1,2,3,}", EscapePolicy::Reject).unwrap();
+    assert_eq!(expr.to_regex(EscapePolicy::Reject), "^f[1-3]?$");
+}
+
+#[test]
+fn test_escaped_literals() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    assert_exact(
+        &["a,b/x.txt", "a,b/{y}.txt", "c (1)/[z].txt"],
+        &["a/x.txt", "a,b/y.txt", "c 1/z.txt"],
+        &config,
+    );
+}
+
+#[test]
+fn test_case_folding_is_case_insensitive() {
+    let config = BraceConfig {
+        path_style: PathStyle::Windows,
+        ..Default::default()
+    };
+    let pattern = assert_exact(&[r"C:\Src\a.rs", r"c:\src\b.rs"], &[], &config);
+    assert!(pattern.starts_with("(?i)^"));
+    assert!(Regex::new(&pattern).unwrap().is_match(r"C:\SRC\B.RS"));
+
+    let config = BraceConfig {
+        case_folding: CaseFolding::Off,
+        ..Default::default()
+    };
+    assert!(!brace_paths_regex(&["a"], &config)
+        .unwrap()
+        .starts_with("(?i)"));
+}
+
+#[test]
+fn test_matches_exactly_the_expansion() {
+    for expr in [
+        "src/{a,b/{c,d{,.bak}}}.rs",
+        "{0,1,2,3,4,5,6,7,8,9,10,11,12,13,99}/x",
+        "img{001,002,003,004,005,006,007,008,009,010}.png",
+        "x{a,}{b,}{c,}",
+    ] {
+        let paths = expand_braces(expr);
+        let config = BraceConfig::default();
+        let pattern = brace_paths_expr(&paths, &config)
+            .unwrap()
+            .to_regex(config.escape);
+        let regex = Regex::new(&pattern).unwrap();
+        for path in &paths {
+            assert!(regex.is_match(path), "{} should match {}", pattern, path);
+        }
+        for path in ["src/a.rs.bak", "100/x", "img0010.png", "xba", "img011.png"] {
+            assert_eq!(
+                regex.is_match(path),
+                paths.iter().any(|p| p == path),
+                "{} on {}",
+                pattern,
+                path
+            );
+        }
+    }
+}
+
+#[test]
+fn test_regex_matches_parsed_expressions() {
+    let expr = BraceExpr::parse("{a,{b,c}d,}x{1,2,3}{,}", EscapePolicy::Reject).unwrap();
+    assert_eq!(
+        expr.to_regex(EscapePolicy::Reject),
+        "^(?:a|(?:b|c)d)?x[1-3]$"
+    );
+}
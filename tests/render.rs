@@ -0,0 +1,89 @@
+use braces::{
+    brace_paths, brace_paths_expr, BraceConfig, BraceExpr, BraceRenderer, EscapePolicy, Renderer,
+};
+
+/// Marks each part with its kind and depth
+struct Marker;
+
+impl Renderer for Marker {
+    fn literal(&self, out: &mut String, text: &str, _written: &str) {
+        out.push_str(&format!("[{}]", text));
+    }
+
+    fn group_open(&self, out: &mut String, depth: usize) {
+        out.push_str(&format!("<{}", depth));
+    }
+
+    fn item_separator(&self, out: &mut String, depth: usize) {
+        out.push_str(&format!("|{}", depth));
+    }
+
+    fn group_close(&self, out: &mut String, depth: usize) {
+        out.push_str(&format!("{}>", depth));
+    }
+
+    fn empty_alternative(&self, out: &mut String, _depth: usize) {
+        out.push('∅');
+    }
+}
+
+#[test]
+fn test_default_renderer_matches_output() {
+    let cases: Vec<(Vec<&str>, BraceConfig)> = vec![
+        (vec!["a/b.rs", "a/c.rs"], BraceConfig::default()),
+        (vec!["a/b", "a/b/c", "d"], BraceConfig::default()),
+        (
+            vec!["a,b.txt", "c.txt", "x/{y}"],
+            BraceConfig {
+                escape: EscapePolicy::Backslash,
+                ..Default::default()
+            },
+        ),
+        (
+            vec!["src/a/1", "src/a/2", "src/b/3", "lib/4"],
+            BraceConfig::default(),
+        ),
+    ];
+    for (paths, config) in cases {
+        let expr = brace_paths_expr(&paths, &config).unwrap();
+        assert_eq!(
+            expr.render(&BraceRenderer, config.escape),
+            brace_paths(&paths, &config).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_render_depths() {
+    let paths = vec!["src/a/1", "src/a/2", "src/b/3", "lib/4"];
+    let expr = brace_paths_expr(&paths, &BraceConfig::default()).unwrap();
+    assert_eq!(expr.to_string(), "{src/{a/{1,2},b/3},lib/4}");
+    assert_eq!(
+        expr.render(&Marker, EscapePolicy::Reject),
+        "<0[src/]<1[a/]<2[1]|2[2]2>|1[b/3]1>|0[lib/4]0>"
+    );
+}
+
+#[test]
+fn test_render_empty_alternative() {
+    let expr = BraceExpr::Concat(vec![
+        BraceExpr::Literal("a/b".into()),
+        BraceExpr::Alt(vec![BraceExpr::empty(), BraceExpr::Literal("/c".into())]),
+    ]);
+    assert_eq!(expr.to_string(), "a/b{,/c}");
+    assert_eq!(
+        expr.render(&Marker, EscapePolicy::Reject),
+        "[a/b]<0∅|0[/c]0>"
+    );
+}
+
+#[test]
+fn test_literals_are_given_plain_text() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let expr = brace_paths_expr(&["x/a,b", "x/c"], &config).unwrap();
+    assert_eq!(expr.to_string(), r"x/{a\,b,c}");
+    assert_eq!(expr.render(&Marker, config.escape), "[x/]<0[a,b]|0[c]0>");
+}
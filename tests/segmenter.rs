@@ -0,0 +1,118 @@
+use braces::{
+    brace_paths, brace_paths_with, BraceConfig, Direction, MultiSeparatorSegmenter, Segmenter,
+    SeparatorSegmenter, TokenSegmenter,
+};
+
+#[test]
+fn test_separator_segmenter() {
+    let metrics = vec![
+        "http.server.requests.count",
+        "http.server.requests.sum",
+        "http.client.requests.count",
+    ];
+    let result = brace_paths_with(
+        &metrics,
+        &BraceConfig::default(),
+        &SeparatorSegmenter::new("."),
+    )
+    .unwrap();
+    assert_eq!(
+        result,
+        "http.{server.requests.{count,sum},client.requests.count}"
+    );
+}
+
+#[test]
+fn test_separator_segmenter_matches_paths() {
+    let paths = vec!["/usr/lib/a.so", "/usr/lib/b.so", "/usr/bin/c"];
+    let config = BraceConfig::default();
+    let segmenter = SeparatorSegmenter::new("/");
+    assert_eq!(
+        brace_paths_with(&paths, &config, &segmenter).unwrap(),
+        brace_paths(&paths, &config).unwrap()
+    );
+}
+
+#[test]
+fn test_separator_segmenter_right_to_left() {
+    let hosts = vec!["api.prod.example.com", "web.prod.example.com"];
+    let config = BraceConfig {
+        direction: Direction::RightToLeft,
+        ..Default::default()
+    };
+    let result = brace_paths_with(&hosts, &config, &SeparatorSegmenter::new(".")).unwrap();
+    assert_eq!(result, "{api,web}.prod.example.com");
+}
+
+#[test]
+fn test_multi_separator_segments() {
+    let segmenter = MultiSeparatorSegmenter::new([".", "$", "::"]);
+    assert_eq!(
+        segmenter.segments("com.example.Outer$Inner"),
+        vec!["com.", "example.", "Outer$", "Inner"]
+    );
+    assert_eq!(segmenter.segments("std::io::"), vec!["std::", "io::"]);
+    assert_eq!(segmenter.segments(""), vec![""]);
+}
+
+#[test]
+fn test_multi_separator_segmenter() {
+    let topics = vec![
+        "orders.eu-west.v1",
+        "orders.us-east.v1",
+        "orders.eu-north.v2",
+    ];
+    let segmenter = MultiSeparatorSegmenter::new([".", "-"]);
+    let result = brace_paths_with(&topics, &BraceConfig::default(), &segmenter).unwrap();
+    assert_eq!(result, "orders.{eu-{west.v1,north.v2},us-east.v1}");
+}
+
+#[test]
+fn test_token_segments() {
+    let segments = |text| TokenSegmenter.segments(text);
+    assert_eq!(
+        segments("HttpServerRequests"),
+        vec!["Http", "Server", "Requests"]
+    );
+    assert_eq!(segments("XMLHttpRequest"), vec!["XML", "Http", "Request"]);
+    assert_eq!(segments("parseV2Config"), vec!["parse", "V2", "Config"]);
+    assert_eq!(
+        segments("kafka_topic-name"),
+        vec!["kafka_", "topic-", "name"]
+    );
+    assert_eq!(segments("_private"), vec!["_", "private"]);
+    assert_eq!(
+        segments("Ünïcode\u{301}Wörd"),
+        vec!["Ünïcode\u{301}", "Wörd"]
+    );
+}
+
+#[test]
+fn test_token_segmenter() {
+    let classes = vec!["XMLHttpRequest", "XMLHttpResponse", "JsonParser"];
+    let result = brace_paths_with(&classes, &BraceConfig::default(), &TokenSegmenter).unwrap();
+    assert_eq!(result, "{XMLHttp{Request,Response},JsonParser}");
+}
+
+#[test]
+fn test_custom_segmenter_without_segment_split() {
+    struct Dots;
+
+    impl Segmenter for Dots {
+        fn segments<'p>(&self, path: &'p str) -> Vec<&'p str> {
+            path.split('.').collect()
+        }
+
+        fn separator(&self) -> &str {
+            "."
+        }
+    }
+
+    let names = vec!["a.b.c", "a.d.e"];
+    let config = BraceConfig {
+        allow_segment_split: false,
+        ..Default::default()
+    };
+    let result = brace_paths_with(&names, &config, &Dots).unwrap();
+    assert_eq!(result, "a.{b.c,d.e}");
+}
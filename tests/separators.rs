@@ -1,4 +1,4 @@
-use braces::{brace_paths, BraceConfig};
+use braces::{brace_paths, expand_braces, BraceConfig, BraceError};
 
 #[test]
 fn test_mixed_separators_normalized() {
@@ -21,3 +21,92 @@ fn test_custom_separator() {
     let result = brace_paths(&paths, &config).unwrap();
     assert_eq!(result, "foo::{bar,baz}");
 }
+
+#[test]
+fn test_colons_are_not_separators_by_default() {
+    let paths = vec!["C:/foo/a.rs", "C:/foo/b.rs", "file:c.rs"];
+    let result = brace_paths(&paths, &BraceConfig::default()).unwrap();
+    assert_eq!(result, "{C:/foo/{a,b},file:c}.rs");
+}
+
+#[test]
+fn test_mixed_separators_keep_colons() {
+    let paths = vec!["file:a/x", "file:a\\y"];
+    let config = BraceConfig {
+        allow_mixed_separators: true,
+        ..Default::default()
+    };
+    let result = brace_paths(&paths, &config).unwrap();
+    assert_eq!(result, "file:a/{x,y}");
+}
+
+#[test]
+fn test_multi_character_separators() {
+    let config = BraceConfig {
+        path_separator: "::".to_string(),
+        separators: vec!["::".to_string(), ".".to_string()],
+        allow_mixed_separators: true,
+        ..Default::default()
+    };
+    let paths = vec!["std::io::Read", "std.io.Write"];
+    let result = brace_paths(&paths, &config).unwrap();
+    assert_eq!(result, "std::io::{Read,Write}");
+}
+
+#[test]
+fn test_longest_separator_wins() {
+    let config = BraceConfig {
+        path_separator: "->".to_string(),
+        separators: vec!["-".to_string(), "->".to_string()],
+        ..Default::default()
+    };
+    let paths = vec!["parse->lex->read", "parse->emit"];
+    let result = brace_paths(&paths, &config).unwrap();
+    assert_eq!(result, "parse->{lex->read,emit}");
+
+    let mixed = brace_paths(&["a-b->c"], &config);
+    assert_eq!(
+        mixed,
+        Err(BraceError::MixedSeparators {
+            found: vec!["-".to_string()],
+            expected: "->".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_custom_set_rejects_other_separators() {
+    let config = BraceConfig {
+        separators: vec!["/".to_string(), ":".to_string()],
+        ..Default::default()
+    };
+    let result = brace_paths(&["a/b", "a:c"], &config);
+    assert!(matches!(result, Err(BraceError::MixedSeparators { .. })));
+}
+
+#[test]
+fn test_root_and_empty_segments_kept() {
+    let cases = [
+        (vec!["/a/x", "/a/y"], "/a/{x,y}"),
+        (vec!["/etc/a", "b"], "{/etc/a,b}"),
+        (vec!["b//c.rs", "b/d.rs"], "b/{/c,d}.rs"),
+    ];
+    for (paths, expected) in cases {
+        let result = brace_paths(&paths, &BraceConfig::default()).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(expand_braces(&result), paths);
+    }
+}
+
+#[test]
+fn test_trailing_separator_kept_in_group() {
+    let cases = [
+        (vec!["a/x", "dir/"], "{a/x,dir/}"),
+        (vec!["a/dir/", "a/x"], "a/{dir/,x}"),
+    ];
+    for (paths, expected) in cases {
+        let result = brace_paths(&paths, &BraceConfig::default()).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(expand_braces(&result), paths);
+    }
+}
@@ -0,0 +1,89 @@
+use braces::{brace_sequences, BraceConfig, BraceError, EscapePolicy, VerifyMode};
+
+fn verified() -> BraceConfig {
+    BraceConfig {
+        verify: VerifyMode::Error,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_tokens_with_slashes_are_not_split() {
+    let sequences = vec![
+        vec!["refs", "heads/main"],
+        vec!["refs", "heads/dev"],
+        vec!["refs", "tags/v1"],
+    ];
+    let result = brace_sequences(&sequences, "/", &verified()).unwrap();
+    assert_eq!(result, "refs/{heads/main,heads/dev,tags/v1}");
+}
+
+#[test]
+fn test_custom_joiner() {
+    let sequences = vec![
+        vec!["tests", "parser", "test_empty"],
+        vec!["tests", "parser", "test_nested"],
+        vec!["tests", "lexer", "test_empty"],
+    ];
+    let result = brace_sequences(&sequences, "::", &verified()).unwrap();
+    assert_eq!(
+        result,
+        "tests::{parser::{test_empty,test_nested},lexer::test_empty}"
+    );
+}
+
+#[test]
+fn test_shared_extension_of_last_token() {
+    let sequences = vec![vec!["src", "a.rs"], vec!["src", "b.rs"]];
+    let result = brace_sequences(&sequences, "/", &verified()).unwrap();
+    assert_eq!(result, "src/{a,b}.rs");
+}
+
+#[test]
+fn test_sort_uses_token_order() {
+    let config = BraceConfig {
+        sort_items: true,
+        ..verified()
+    };
+    let sequences = vec![vec![0, 10], vec![0, 2], vec![0, 1]];
+    let result = brace_sequences(&sequences, "/", &config).unwrap();
+    assert_eq!(result, "0/{1,2,10}");
+}
+
+#[test]
+fn test_json_pointer_tokens() {
+    let sequences: Vec<Vec<String>> = vec![
+        vec!["".into(), "items".into(), "0".into(), "name".into()],
+        vec!["".into(), "items".into(), "1".into(), "name".into()],
+    ];
+    let result = brace_sequences(&sequences, "/", &verified()).unwrap();
+    assert_eq!(result, "/items/{0/name,1/name}");
+}
+
+#[test]
+fn test_duplicates_removed() {
+    let sequences = vec![vec!["a", "b"], vec!["a", "b"], vec!["a", "c"]];
+    let result = brace_sequences(&sequences, "/", &verified()).unwrap();
+    assert_eq!(result, "a/{b,c}");
+}
+
+#[test]
+fn test_metacharacters_in_tokens() {
+    let sequences = vec![vec!["a", "x,y"], vec!["a", "z"]];
+    let result = brace_sequences(&sequences, "/", &verified());
+    assert!(matches!(result, Err(BraceError::InvalidBraceInput { .. })));
+
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..verified()
+    };
+    let result = brace_sequences(&sequences, "/", &config).unwrap();
+    assert_eq!(result, r"a/{x\,y,z}");
+}
+
+#[test]
+fn test_empty_input() {
+    let sequences: Vec<Vec<&str>> = vec![];
+    let result = brace_sequences(&sequences, "/", &BraceConfig::default());
+    assert_eq!(result, Err(BraceError::EmptyInput));
+}
@@ -0,0 +1,92 @@
+use braces::{brace_paths, expand_braces_with, BraceConfig, EscapePolicy};
+use std::process::Command;
+
+fn shell_config() -> BraceConfig {
+    BraceConfig {
+        escape: EscapePolicy::Shell,
+        separators: vec!["/".to_string()],
+        ..Default::default()
+    }
+}
+
+/// Expand an expression with bash, or `None` if bash is not available
+fn bash_expand(expr: &str) -> Option<Vec<String>> {
+    let output = Command::new("bash")
+        .arg("-c")
+        .arg(format!("printf '%s\\0' {}", expr))
+        .output()
+        .ok()?;
+    assert!(output.status.success(), "bash failed on {}", expr);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    Some(
+        stdout
+            .split_terminator('\0')
+            .map(|s| s.to_string())
+            .collect(),
+    )
+}
+
+fn assert_shell_safe(paths: Vec<String>) {
+    let result = brace_paths(&paths, &shell_config()).unwrap();
+
+    let mut expected = paths.clone();
+    expected.sort();
+    let mut expanded = expand_braces_with(&result, EscapePolicy::Shell);
+    expanded.sort();
+    assert_eq!(expanded, expected, "Expansion of {}", result);
+
+    if let Some(mut from_bash) = bash_expand(&result) {
+        from_bash.sort();
+        assert_eq!(from_bash, expected, "bash expansion of {}", result);
+    }
+}
+
+#[test]
+fn test_shell_quotes_only_literals() {
+    let result = brace_paths(&["my dir/a b.txt", "my dir/c.txt"], &shell_config()).unwrap();
+    assert_eq!(result, "'my dir'/{'a b',c}.txt");
+}
+
+#[test]
+fn test_shell_leaves_safe_paths_unquoted() {
+    let result = brace_paths(&["src/lib.rs", "src/main.rs"], &shell_config()).unwrap();
+    assert_eq!(result, "src/{lib,main}.rs");
+}
+
+#[test]
+fn test_shell_embedded_quote() {
+    let result = brace_paths(&["it's/a", "it's/b"], &shell_config()).unwrap();
+    assert_eq!(result, r"'it'\''s'/{a,b}");
+}
+
+#[test]
+fn test_shell_every_ascii_character() {
+    for byte in 1u8..128 {
+        let c = byte as char;
+        // Only `/` is recognised as a separator, so backslashes are literal
+        if c == '/' {
+            continue;
+        }
+        assert_shell_safe(vec![
+            format!("dir/a{}b", c),
+            format!("dir/{}", c),
+            format!("{}x/c", c),
+        ]);
+    }
+}
+
+#[test]
+fn test_shell_injection_attempts() {
+    assert_shell_safe(vec![
+        "$(touch pwned)".to_string(),
+        "`id`/x".to_string(),
+        "a; rm -rf b/c".to_string(),
+        "x && y/z".to_string(),
+        "*/?/[ab]".to_string(),
+        "~root/$HOME/${PATH}".to_string(),
+        "!!/#comment".to_string(),
+        "\"dq\"/'sq'".to_string(),
+        "line\nbreak/tab\there".to_string(),
+        "ünïcødé/ファイル".to_string(),
+    ]);
+}
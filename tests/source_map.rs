@@ -0,0 +1,122 @@
+use braces::{brace_paths_mapped, BraceConfig, EscapePolicy, MappedBraces, PathStyle, SpanKind};
+
+/// Each span's text and kind, with the inputs it covers
+fn spans(mapped: &MappedBraces) -> Vec<(&str, SpanKind, Vec<usize>)> {
+    mapped
+        .spans
+        .iter()
+        .map(|s| (&mapped.text[s.span.clone()], s.kind, s.inputs.clone()))
+        .collect()
+}
+
+#[test]
+fn test_spans_cover_their_inputs() {
+    let paths = vec!["src/a.rs", "src/b/c.rs", "src/b/d.rs"];
+    let mapped = brace_paths_mapped(&paths, &BraceConfig::default()).unwrap();
+    assert_eq!(mapped.text, "src/{a,b/{c,d}}.rs");
+    assert_eq!(
+        spans(&mapped),
+        vec![
+            ("src/", SpanKind::Literal, vec![0, 1, 2]),
+            ("{a,b/{c,d}}", SpanKind::Group, vec![0, 1, 2]),
+            ("a", SpanKind::Literal, vec![0]),
+            ("b/", SpanKind::Literal, vec![1, 2]),
+            ("{c,d}", SpanKind::Group, vec![1, 2]),
+            ("c", SpanKind::Literal, vec![1]),
+            ("d", SpanKind::Literal, vec![2]),
+            (".rs", SpanKind::Literal, vec![0, 1, 2]),
+        ]
+    );
+    assert_eq!(mapped.span_at(10).unwrap().inputs, vec![1]);
+    assert_eq!(mapped.span_at(9).unwrap().kind, SpanKind::Group);
+}
+
+#[test]
+fn test_duplicate_and_reprocessed_inputs() {
+    let config = BraceConfig {
+        reprocess_braces: true,
+        ..Default::default()
+    };
+    let paths = vec!["x/{a,b}", "x/c", "x/a"];
+    let mapped = brace_paths_mapped(&paths, &config).unwrap();
+    assert_eq!(mapped.text, "x/{a,b,c}");
+    assert_eq!(
+        spans(&mapped)[2..],
+        [
+            ("a", SpanKind::Literal, vec![0, 2]),
+            ("b", SpanKind::Literal, vec![0]),
+            ("c", SpanKind::Literal, vec![1]),
+        ]
+    );
+}
+
+#[test]
+fn test_split_segments_and_escapes() {
+    let mapped = brace_paths_mapped(&["a/b", "a/b/c"], &BraceConfig::default()).unwrap();
+    assert_eq!(mapped.text, "a/b/{c,}");
+    assert_eq!(
+        spans(&mapped),
+        vec![
+            ("a/b/", SpanKind::Literal, vec![0, 1]),
+            ("{c,}", SpanKind::Group, vec![0, 1]),
+            ("c", SpanKind::Literal, vec![1]),
+        ]
+    );
+
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..Default::default()
+    };
+    let mapped = brace_paths_mapped(&["d/x,y", "d/z"], &config).unwrap();
+    assert_eq!(mapped.text, r"d/{x\,y,z}");
+    assert_eq!(mapped.span_at(4).unwrap().inputs, vec![0]);
+}
+
+#[test]
+fn test_case_folded_inputs() {
+    let config = BraceConfig {
+        path_style: PathStyle::Windows,
+        ..Default::default()
+    };
+    let mapped = brace_paths_mapped(&[r"C:\a.rs", r"c:\A.rs", r"c:\b.rs"], &config).unwrap();
+    assert_eq!(mapped.text, r"C:\{a,b}.rs");
+    assert_eq!(mapped.span_at(4).unwrap().inputs, vec![0, 1]);
+}
+
+#[test]
+fn test_pretty_keeps_spans() {
+    let paths = vec!["src/a.rs", "src/b/c.rs", "src/b/d.rs"];
+    let mapped = brace_paths_mapped(&paths, &BraceConfig::default()).unwrap();
+    let pretty = mapped.pretty(EscapePolicy::Reject);
+    assert_eq!(
+        pretty.text,
+        "src/{\n     a,\n     b/{\n        c,\n        d\n       }\n    }\n.rs"
+    );
+    for (plain, moved) in mapped.spans.iter().zip(&pretty.spans) {
+        assert_eq!(plain.inputs, moved.inputs);
+        let text = &pretty.text[moved.span.clone()];
+        let squeezed: String = text.split_whitespace().collect();
+        assert_eq!(squeezed, mapped.text[plain.span.clone()]);
+    }
+}
+
+#[cfg(all(feature = "cli", feature = "highlight"))]
+#[test]
+fn test_highlight_keeps_spans() {
+    let config = BraceConfig {
+        highlight: true,
+        ..Default::default()
+    };
+    let plain = brace_paths_mapped(&["a/x", "a/y/z", "a/y/w"], &BraceConfig::default()).unwrap();
+    let highlighted = brace_paths_mapped(&["a/x", "a/y/z", "a/y/w"], &config).unwrap();
+    assert!(highlighted.text.contains('\x1b'));
+    assert_eq!(plain.spans.len(), highlighted.spans.len());
+    let ansi = regex::Regex::new("\x1b\\[[0-9;]*m").unwrap();
+    for (plain_span, span) in plain.spans.iter().zip(&highlighted.spans) {
+        let expected = &plain.text[plain_span.span.clone()];
+        let text = &highlighted.text[span.span.clone()];
+        let visible = ansi.replace_all(text, "");
+        assert_eq!(visible, expected);
+        assert_eq!(plain_span.inputs, span.inputs);
+    }
+}
@@ -0,0 +1,79 @@
+use braces::{brace_paths, brace_paths_with_hint, BraceConfig};
+
+fn stable() -> BraceConfig {
+    BraceConfig {
+        stable_output: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_default_mode_reshapes_on_new_suffix() {
+    let before = brace_paths(
+        &["src/a.rs", "src/b.rs", "tests/c.rs"],
+        &BraceConfig::default(),
+    );
+    let after = brace_paths(
+        &["src/a.rs", "src/b.rs", "tests/c.rs", "README.md"],
+        &BraceConfig::default(),
+    );
+    assert_eq!(before.unwrap(), "{src/{a,b},tests/c}.rs");
+    assert_eq!(after.unwrap(), "{src/{a.rs,b.rs},tests/c.rs,README.md}");
+}
+
+#[test]
+fn test_stable_mode_keeps_other_groups() {
+    let before = brace_paths(&["src/a.rs", "src/b.rs", "tests/c.rs"], &stable());
+    let after = brace_paths(
+        &["src/a.rs", "src/b.rs", "tests/c.rs", "README.md"],
+        &stable(),
+    );
+    assert_eq!(before.unwrap(), "{src/{a,b}.rs,tests/c.rs}");
+    assert_eq!(after.unwrap(), "{src/{a,b}.rs,tests/c.rs,README.md}");
+}
+
+#[test]
+fn test_stable_mode_change_confined_to_group() {
+    let before = brace_paths(
+        &["src/a.rs", "src/b.rs", "docs/x.md", "docs/y.md"],
+        &stable(),
+    );
+    let after = brace_paths(
+        &[
+            "src/a.rs",
+            "src/b.rs",
+            "docs/x.md",
+            "docs/y.md",
+            "docs/z.txt",
+        ],
+        &stable(),
+    );
+    assert_eq!(before.unwrap(), "{src/{a,b}.rs,docs/{x,y}.md}");
+    assert_eq!(after.unwrap(), "{src/{a,b}.rs,docs/{x.md,y.md,z.txt}}");
+}
+
+#[test]
+fn test_stable_mode_stem_split_only_in_leaf_groups() {
+    let config = BraceConfig {
+        stable_output: true,
+        allow_stem_split: true,
+        ..Default::default()
+    };
+    let paths = vec!["a/foo1", "a/foo2", "ab/x/y"];
+    let result = brace_paths(&paths, &config).unwrap();
+    assert_eq!(result, "{a/foo{1,2},ab/x/y}");
+
+    let config = BraceConfig {
+        stable_output: false,
+        ..config
+    };
+    let result = brace_paths(&paths, &config).unwrap();
+    assert_eq!(result, "a{/foo{1,2},b/x/y}");
+}
+
+#[test]
+fn test_hint_preserves_previous_order() {
+    let paths = vec!["z/new", "z/b", "z/a"];
+    let result = brace_paths_with_hint(&paths, &stable(), "z/{a,b}").unwrap();
+    assert_eq!(result, "z/{a,b,new}");
+}
@@ -0,0 +1,133 @@
+use braces::{
+    summarise_paths, summarise_sized_paths, BaseDisplay, BraceConfig, CaseFolding, OutsideBase,
+};
+
+fn listing() -> Vec<&'static str> {
+    vec![
+        "src/lib.rs",
+        "src/processor/trie.rs",
+        "src/processor/expansion.rs",
+        "tests/basic.rs",
+        "tests/depth.rs",
+        "README.md",
+    ]
+}
+
+#[test]
+fn test_zoom_depth_1() {
+    let result = summarise_paths(&listing(), &BraceConfig::default(), 1).unwrap();
+    assert_eq!(result, "{src(3),tests(2),README.md}");
+}
+
+#[test]
+fn test_zoom_depth_2() {
+    let result = summarise_paths(&listing(), &BraceConfig::default(), 2).unwrap();
+    assert_eq!(
+        result,
+        "{src/{lib.rs,processor(2)},tests/{basic.rs,depth.rs},README.md}"
+    );
+}
+
+#[test]
+fn test_zoom_deeper_than_tree_lists_everything() {
+    let result = summarise_paths(&listing(), &BraceConfig::default(), 5).unwrap();
+    assert_eq!(
+        result,
+        "{src/{lib.rs,processor/{trie.rs,expansion.rs}},tests/{basic.rs,depth.rs},README.md}"
+    );
+}
+
+#[test]
+fn test_zoom_sorted() {
+    let config = BraceConfig {
+        sort_items: true,
+        ..Default::default()
+    };
+    let result = summarise_paths(&listing(), &config, 1).unwrap();
+    assert_eq!(result, "{README.md,src(3),tests(2)}");
+}
+
+#[test]
+fn test_zoom_with_sizes() {
+    let paths = vec![
+        ("src/a.rs", 512),
+        ("src/b.rs", 512),
+        ("src/a.rs", 512),
+        ("assets/big.bin", 3 * 1024 * 1024),
+    ];
+    let result = summarise_sized_paths(&paths, &BraceConfig::default(), 1).unwrap();
+    assert_eq!(result, "{src(2; 1.0 KiB),assets(1; 3.0 MiB)}");
+}
+
+#[test]
+fn test_zoom_depth_0_is_root_only() {
+    let result = summarise_paths(&listing(), &BraceConfig::default(), 0).unwrap();
+    assert_eq!(result, "(6)");
+    let sized = vec![("src/a.rs", 1024), ("README.md", 1024)];
+    let result = summarise_sized_paths(&sized, &BraceConfig::default(), 0).unwrap();
+    assert_eq!(result, "(2; 2.0 KiB)");
+}
+
+#[test]
+fn test_sized_deduplication_folds_case() {
+    let config = BraceConfig {
+        case_folding: CaseFolding::Lowercase,
+        ..Default::default()
+    };
+    let paths = ["Src/A", "src/a"];
+    let sized = vec![(paths[0], 1), (paths[1], 1)];
+    assert_eq!(summarise_paths(&paths, &config, 0).unwrap(), "(1)");
+    assert_eq!(
+        summarise_sized_paths(&sized, &config, 0).unwrap(),
+        "(1; 1 B)"
+    );
+}
+
+#[test]
+fn test_zoom_keeps_shared_root() {
+    let paths = vec!["/x/a.rs", "/x/b.rs", "/y/c.rs"];
+    let config = BraceConfig::default();
+    assert_eq!(summarise_paths(&paths, &config, 0).unwrap(), "(3)");
+    assert_eq!(summarise_paths(&paths, &config, 1).unwrap(), "/{x(2),y(1)}");
+    assert_eq!(
+        summarise_paths(&paths, &config, 2).unwrap(),
+        "/{x/{a.rs,b.rs},y/c.rs}"
+    );
+}
+
+#[test]
+fn test_zoom_relative_to_base() {
+    let paths = vec![
+        "/repo/src/a.rs",
+        "/repo/src/b.rs",
+        "/repo/README.md",
+        "/usr/include/c.h",
+    ];
+    let config = BraceConfig {
+        base: Some("/repo".into()),
+        outside_base: OutsideBase::KeepAbsolute,
+        ..Default::default()
+    };
+    assert_eq!(
+        summarise_paths(&paths, &config, 1).unwrap(),
+        "{{src(2),README.md},/usr(1)}"
+    );
+}
+
+#[test]
+fn test_zoom_sized_with_base_variable() {
+    let paths = vec![
+        ("/repo/src/a.rs", 1024),
+        ("/repo/src/b.rs", 1024),
+        ("/repo/README.md", 5),
+    ];
+    let config = BraceConfig {
+        base: Some("/repo".into()),
+        base_display: BaseDisplay::Variable("$ROOT".into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        summarise_sized_paths(&paths, &config, 1).unwrap(),
+        "$ROOT/{src(2; 2.0 KiB),README.md}"
+    );
+}
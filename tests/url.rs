@@ -0,0 +1,113 @@
+use braces::{brace_paths, expand_braces, BraceConfig, Direction, PathStyle, VerifyMode};
+
+fn url_mode() -> BraceConfig {
+    BraceConfig {
+        path_style: PathStyle::Url,
+        verify: VerifyMode::Error,
+        ..Default::default()
+    }
+}
+
+fn assert_url_braces(urls: Vec<&str>, expected: &str) {
+    let result = brace_paths(&urls, &url_mode()).unwrap();
+    assert_eq!(result, expected);
+
+    let mut expanded = expand_braces(&result);
+    expanded.sort();
+    let mut urls = urls;
+    urls.sort();
+    assert_eq!(expanded, urls);
+}
+
+#[test]
+fn test_shared_authority() {
+    assert_url_braces(
+        vec!["https://a.example.com/v1/x", "https://a.example.com/v1/y"],
+        "https://a.example.com/v1/{x,y}",
+    );
+}
+
+#[test]
+fn test_different_hosts() {
+    assert_url_braces(
+        vec![
+            "https://a.example.com/v1/users",
+            "https://b.example.com/v1/users",
+            "http://a.example.com/health",
+        ],
+        "{https://a.example.com/v1/users,https://b.example.com/v1/users,http://a.example.com/health}",
+    );
+}
+
+#[test]
+fn test_query_parameters_are_a_level() {
+    assert_url_braces(
+        vec![
+            "https://x.io/search?q=a&page=1",
+            "https://x.io/search?q=a&page=2",
+            "https://x.io/search?q=b",
+        ],
+        "https://x.io/search?{q=a&{page=1,page=2},q=b}",
+    );
+}
+
+#[test]
+fn test_trailing_slash_and_bare_host() {
+    assert_url_braces(
+        vec!["https://x.io", "https://x.io/", "https://x.io/docs/"],
+        "https://x.io{/,/docs/,}",
+    );
+}
+
+#[test]
+fn test_fragment_and_ports() {
+    assert_url_braces(
+        vec![
+            "http://localhost:8080/app#top",
+            "http://localhost:8080/app#end",
+        ],
+        "http://localhost:8080/app#{top,end}",
+    );
+}
+
+#[test]
+fn test_relative_urls() {
+    assert_url_braces(vec!["/api/a?x=1", "/api/b"], "/api/{a?x=1,b}");
+}
+
+#[test]
+fn test_path_and_query_levels() {
+    assert_url_braces(
+        vec![
+            "https://api.example.com/v1/users?page=1",
+            "https://api.example.com/v1/users?page=2",
+            "https://api.example.com/v1/groups",
+        ],
+        "https://api.example.com/v1/{users?{page=1,page=2},groups}",
+    );
+}
+
+#[test]
+fn test_url_right_to_left() {
+    let config = BraceConfig {
+        path_style: PathStyle::Url,
+        direction: Direction::RightToLeft,
+        ..Default::default()
+    };
+    let urls = vec!["https://a.com/x/v1", "https://b.com/y/v1"];
+    let result = brace_paths(&urls, &config).unwrap();
+    assert_eq!(result, "https://{a.com/x,b.com/y}/v1");
+    assert_eq!(expand_braces(&result), urls);
+}
+
+#[test]
+fn test_max_brace_size_in_url_mode() {
+    let config = BraceConfig {
+        max_brace_size: Some(2),
+        ..url_mode()
+    };
+    let urls = vec!["https://x.io/a", "https://x.io/b", "https://x.io/c"];
+    let result = brace_paths(&urls, &config).unwrap();
+    assert_eq!(result, "https://x.io/{{a,b},c}");
+    assert_eq!(expand_braces(&result), urls);
+}
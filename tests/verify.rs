@@ -0,0 +1,71 @@
+use braces::{brace_paths, BraceConfig, BraceError, VerifyMode};
+
+#[test]
+fn test_verify_passes_correct_output() {
+    let config = BraceConfig {
+        verify: VerifyMode::Error,
+        ..Default::default()
+    };
+    let result = brace_paths(&["a/b.rs", "a/c.rs", "d/e.rs"], &config).unwrap();
+    assert_eq!(result, "{a/{b,c},d/e}.rs");
+}
+
+#[test]
+fn test_verify_error_lists_differences() {
+    let config = BraceConfig {
+        verify: VerifyMode::Error,
+        allow_segment_split: false,
+        ..Default::default()
+    };
+    let result = brace_paths(&["b/", "b"], &config);
+    assert_eq!(
+        result,
+        Err(BraceError::VerificationFailed {
+            missing: vec!["b/".to_string()],
+            unexpected: vec![],
+        })
+    );
+}
+
+#[test]
+fn test_verify_fallback_to_flat() {
+    let config = BraceConfig {
+        verify: VerifyMode::Fallback,
+        allow_segment_split: false,
+        ..Default::default()
+    };
+    let result = brace_paths(&["b/", "b"], &config).unwrap();
+    assert_eq!(result, "{b/,b}");
+
+    // A lone trailing separator is dropped by the trie, so this falls back too
+    let config = BraceConfig {
+        allow_segment_split: true,
+        ..config
+    };
+    let result = brace_paths(&["a/b", "d/"], &config).unwrap();
+    assert_eq!(result, "{a/b,d/}");
+}
+
+#[test]
+fn test_verify_ordered_mode_checks_sequence() {
+    let config = BraceConfig {
+        verify: VerifyMode::Fallback,
+        preserve_order_within_braces: true,
+        ..Default::default()
+    };
+    let result = brace_paths(&["x/a", "y/a", "x/b"], &config).unwrap();
+    assert_eq!(result, "{x/a,y/a,x/b}");
+
+    let config = BraceConfig {
+        verify: VerifyMode::Error,
+        ..config
+    };
+    let result = brace_paths(&["x/a", "y/a", "x/b"], &config);
+    assert_eq!(
+        result,
+        Err(BraceError::VerificationFailed {
+            missing: vec![],
+            unexpected: vec![],
+        })
+    );
+}
@@ -0,0 +1,104 @@
+use braces::{
+    brace_paths, expand_braces_with, powershell_array, pretty_braces_with, BraceConfig,
+    EscapePolicy, Normalisation, PathStyle, VerifyMode,
+};
+
+fn windows() -> BraceConfig {
+    BraceConfig {
+        path_style: PathStyle::Windows,
+        verify: VerifyMode::Error,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_drive_paths() {
+    let paths = vec![r"C:\Users\x\proj\src\a.rs", r"C:\Users\x\proj\src\b.rs"];
+    let result = brace_paths(&paths, &windows()).unwrap();
+    assert_eq!(result, r"C:\Users\x\proj\src\{a,b}.rs");
+}
+
+#[test]
+fn test_unc_root_is_one_segment() {
+    let paths = vec![
+        r"\\server\share\dir\b.txt",
+        r"\\server\share\dir\c.txt",
+        r"D:\d.txt",
+    ];
+    let result = brace_paths(&paths, &windows()).unwrap();
+    assert_eq!(result, r"{\\server\share\dir\{b,c},D:\d}.txt");
+}
+
+#[test]
+fn test_device_paths() {
+    let paths = vec![r"\\?\UNC\srv\share\a", r"\\?\C:\b", r"C:\c"];
+    let result = brace_paths(&paths, &windows()).unwrap();
+    assert_eq!(result, r"{\\?\UNC\srv\share\a,\\?\C:\b,C:\c}");
+}
+
+#[test]
+fn test_case_insensitive() {
+    let paths = vec![r"C:\Program Files\App\a.dll", r"c:\program files\app\b.dll"];
+    let result = brace_paths(&paths, &windows()).unwrap();
+    assert_eq!(result, r"C:\Program Files\App\{a,b}.dll");
+}
+
+#[test]
+fn test_forward_slashes_accepted() {
+    let config = BraceConfig {
+        allow_mixed_separators: true,
+        ..windows()
+    };
+    let result = brace_paths(&[r"C:\src\a.rs", "C:/src/b.rs"], &config).unwrap();
+    assert_eq!(result, r"C:\src\{a,b}.rs");
+}
+
+#[test]
+fn test_escaping_keeps_backslashes_literal() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..windows()
+    };
+    let paths = vec![r"C:\docs\it's,here.txt", r"C:\docs\b.txt"];
+    let result = brace_paths(&paths, &config).unwrap();
+    assert_eq!(result, r"C:\docs\{'it''s,here',b}.txt");
+    assert_eq!(
+        expand_braces_with(&result, EscapePolicy::QuoteDoubled),
+        paths
+    );
+}
+
+#[test]
+fn test_parent_segments_stay_below_root() {
+    let config = BraceConfig {
+        normalisation: Normalisation {
+            resolve_parent_segments: true,
+            ..Default::default()
+        },
+        ..windows()
+    };
+    let paths = vec![r"C:\..\a", r"\\srv\share\x\..\b"];
+    let result = brace_paths(&paths, &config).unwrap();
+    assert_eq!(result, r"{C:\..\a,\\srv\share\b}");
+}
+
+#[test]
+fn test_powershell_array() {
+    let result = brace_paths(&[r"C:\src\a.rs", r"C:\src\b.rs"], &windows()).unwrap();
+    assert_eq!(
+        powershell_array(&result, &windows()),
+        r"@('C:\src\a.rs', 'C:\src\b.rs')"
+    );
+}
+
+#[test]
+fn test_resolved_escape_reads_output() {
+    let config = BraceConfig {
+        escape: EscapePolicy::Backslash,
+        ..windows()
+    };
+    assert_eq!(config.resolved().escape, EscapePolicy::QuoteDoubled);
+    let result = brace_paths(&[r"C:\x\a,b", r"C:\x\c"], &config).unwrap();
+    let pretty = pretty_braces_with(&result, config.resolved().escape);
+    assert_eq!(pretty, "C:\\x\\{\n      'a,b',\n      c\n     }");
+}